@@ -0,0 +1,186 @@
+//! An immutable map constructed at compile time which owns its entries.
+use crate::map::{Entries, Keys, Values};
+use core::fmt;
+use core::iter::IntoIterator;
+use core::ops::Index;
+use phf_shared::{self, HashKey, PhfBorrow, PhfHash};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// An immutable map constructed at compile time which owns its entries.
+///
+/// This is laid out like a `Map`, but holds its `D` displacements and `N`
+/// entries in arrays rather than behind `&'static` slices, so the whole table
+/// lives in a single item with no separate backing data. It has the same
+/// lookup methods as `Map`.
+///
+/// ## Note
+///
+/// The fields of this struct are public so that they may be initialized by
+/// code generation. They are subject to change at any time and should never
+/// be accessed directly.
+pub struct ArrayMap<K, V, const D: usize, const N: usize> {
+    #[doc(hidden)]
+    pub key: HashKey,
+    #[doc(hidden)]
+    pub disps: [(u32, u32); D],
+    #[doc(hidden)]
+    pub entries: [(K, V); N],
+}
+
+impl<K, V, const D: usize, const N: usize> fmt::Debug for ArrayMap<K, V, D, N>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map().entries(self.entries()).finish()
+    }
+}
+
+impl<'a, K, V, T: ?Sized, const D: usize, const N: usize> Index<&'a T> for ArrayMap<K, V, D, N>
+where
+    T: Eq + PhfHash,
+    K: PhfBorrow<T>,
+{
+    type Output = V;
+
+    fn index(&self, k: &'a T) -> &V {
+        self.get(k).expect("no entry found for key")
+    }
+}
+
+impl<K, V, const D: usize, const N: usize> PartialEq for ArrayMap<K, V, D, N>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.disps == other.disps && self.entries == other.entries
+    }
+}
+
+impl<K, V, const D: usize, const N: usize> Eq for ArrayMap<K, V, D, N>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, const D: usize, const N: usize> ArrayMap<K, V, D, N> {
+    /// Returns the number of entries in the `ArrayMap`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns true if the `ArrayMap` is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Determines if `key` is in the `ArrayMap`.
+    pub fn contains_key<T>(&self, key: &T) -> bool
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value that `key` maps to.
+    pub fn get<T>(&self, key: &T) -> Option<&V>
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_entry(key).map(|e| e.1)
+    }
+
+    /// Returns a reference to the map's internal static instance of the given
+    /// key.
+    ///
+    /// This can be useful for interning schemes.
+    pub fn get_key<T>(&self, key: &T) -> Option<&K>
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_entry(key).map(|e| e.0)
+    }
+
+    /// Like `get`, but returns both the key and the value.
+    pub fn get_entry<T>(&self, key: &T) -> Option<(&K, &V)>
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        if D == 0 {
+            return None;
+        } //Prevent panic on empty map
+        let hashes = phf_shared::hash(key, &self.key);
+        let index = phf_shared::get_index(&hashes, &self.disps, N);
+        let entry = &self.entries[index as usize];
+        let b: &T = entry.0.borrow();
+        if b == key {
+            Some((&entry.0, &entry.1))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the key/value pairs in the map.
+    ///
+    /// Entries are returned in an arbitrary but fixed order.
+    pub fn entries(&self) -> Entries<'_, K, V> {
+        Entries {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys in the map.
+    ///
+    /// Keys are returned in an arbitrary but fixed order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.entries(),
+        }
+    }
+
+    /// Returns an iterator over the values in the map.
+    ///
+    /// Values are returned in an arbitrary but fixed order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.entries(),
+        }
+    }
+}
+
+impl<'a, K, V, const D: usize, const N: usize> IntoIterator for &'a ArrayMap<K, V, D, N> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Entries<'a, K, V>;
+
+    fn into_iter(self) -> Entries<'a, K, V> {
+        self.entries()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V, const D: usize, const N: usize> Serialize for ArrayMap<K, V, D, N>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.entries() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
@@ -29,6 +29,28 @@
 //! phf = { version = "0.13.1", default-features = false }
 //! ```
 //!
+//! ## Key and value types
+//!
+//! The entries of PHF data structures are stored in `&'static` slices which
+//! are built into the binary, so every key and value type must be `'static`
+//! and every key and value must be a constant expression. This holds whether
+//! or not the `std` feature is enabled, and the `'static` bounds on the data
+//! structures cannot be relaxed as the entries borrow nothing else.
+//!
+//! In particular, heap allocated types such as `String` and `Vec<T>` cannot
+//! hold any data in a constant expression. Use `&'static str` and
+//! `&'static [T]` instead, which work with or without `std` and `alloc`, or
+//! `Cow<'static, str>` with `Cow::Borrowed` values where an owned type is
+//! needed by an API:
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//!
+//! static ALIASES: phf::Map<&'static str, &'static [&'static str]> = phf::Map::new();
+//! static NAMES: phf::Map<u32, Cow<'static, str>> = phf::Map::new();
+//! # let _ = (&ALIASES, &NAMES);
+//! ```
+//!
 //! ## Example (with the `macros` feature enabled)
 //!
 //! ```rust
@@ -180,6 +202,8 @@ pub use phf_macros::phf_set;
 /// Requires the `macros` feature. Same usage as [`phf_set`].
 pub use phf_macros::phf_ordered_set;
 
+#[doc(inline)]
+pub use self::array_map::ArrayMap;
 #[doc(inline)]
 pub use self::map::Map;
 #[doc(inline)]
@@ -188,9 +212,19 @@ pub use self::ordered_map::OrderedMap;
 pub use self::ordered_set::OrderedSet;
 #[doc(inline)]
 pub use self::set::Set;
+#[doc(inline)]
+pub use self::sorted_map::SortedMap;
 pub use phf_shared::PhfHash;
 
+// Used by the functions generated by `phf_codegen::Dispatcher`.
+#[doc(hidden)]
+pub mod __private {
+    pub use phf_shared::{get_index, hash, PhfBorrow};
+}
+
+pub mod array_map;
 pub mod map;
 pub mod ordered_map;
 pub mod ordered_set;
 pub mod set;
+pub mod sorted_map;
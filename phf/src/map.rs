@@ -1,5 +1,6 @@
 //! An immutable map constructed at compile time.
 use core::fmt;
+use core::hash::{Hash, Hasher};
 use core::iter::FusedIterator;
 use core::iter::IntoIterator;
 use core::ops::Index;
@@ -42,7 +43,7 @@ where
     type Output = V;
 
     fn index(&self, k: &'a T) -> &V {
-        self.get(k).expect("invalid key")
+        self.get(k).expect("no entry found for key")
     }
 }
 
@@ -69,6 +70,20 @@ where
 {
 }
 
+/// Hashes the entries of the map in iteration order.
+///
+/// The iteration order depends on the generated table, so the hash is only
+/// stable for a single build of the table.
+impl<K, V> Hash for Map<K, V>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.entries.hash(state)
+    }
+}
+
 impl<K, V> Map<K, V> {
     /// Create a new, empty, immutable map.
     #[inline]
@@ -101,6 +116,32 @@ impl<K, V> Map<K, V> {
         self.get(key).is_some()
     }
 
+    /// Determines if any of `keys` is in the `Map`.
+    ///
+    /// Stops at the first key which is found.
+    pub fn contains_any<'k, T, I>(&self, keys: I) -> bool
+    where
+        T: Eq + PhfHash + ?Sized + 'k,
+        K: PhfBorrow<T>,
+        I: IntoIterator<Item = &'k T>,
+    {
+        keys.into_iter().any(|key| self.contains_key(key))
+    }
+
+    /// Returns an iterator over the values that each of `keys` maps to, in
+    /// the order of `keys`.
+    pub fn get_many<'k, T, I>(&self, keys: I) -> GetMany<'_, K, V, I::IntoIter>
+    where
+        T: Eq + PhfHash + ?Sized + 'k,
+        K: PhfBorrow<T>,
+        I: IntoIterator<Item = &'k T>,
+    {
+        GetMany {
+            map: self,
+            keys: keys.into_iter(),
+        }
+    }
+
     /// Returns a reference to the value that `key` maps to.
     pub fn get<T>(&self, key: &T) -> Option<&V>
     where
@@ -110,6 +151,27 @@ impl<K, V> Map<K, V> {
         self.get_entry(key).map(|e| e.1)
     }
 
+    /// Returns a copy of the value that `key` maps to, or `default` if `key`
+    /// is not in the `Map`.
+    pub fn get_or<T>(&self, key: &T, default: V) -> V
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+        V: Copy,
+    {
+        self.get(key).copied().unwrap_or(default)
+    }
+
+    /// Returns a reference to the value that `key` maps to, or `default` if
+    /// `key` is not in the `Map`.
+    pub fn get_ref_or<'a, T>(&'a self, key: &T, default: &'a V) -> &'a V
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get(key).unwrap_or(default)
+    }
+
     /// Returns a reference to the map's internal static instance of the given
     /// key.
     ///
@@ -122,8 +184,53 @@ impl<K, V> Map<K, V> {
         self.get_entry(key).map(|e| e.0)
     }
 
+    /// Interns `key`, returning the map's `'static` instance of it.
+    ///
+    /// This is an alias of [`get_key`](Self::get_key), allowing a borrowed key
+    /// such as a `&str` read at runtime to be exchanged for the key stored in
+    /// the map, which lives for `'static` and can be compared by address.
+    ///
+    /// ```rust
+    /// use phf::phf_map;
+    ///
+    /// static KEYWORDS: phf::Map<&'static str, u32> = phf_map! {
+    ///     "loop" => 0,
+    ///     "fn" => 1,
+    /// };
+    ///
+    /// let input = String::from("loop");
+    /// let interned: &'static str = KEYWORDS.intern(input.as_str()).unwrap();
+    /// assert_eq!("loop", interned);
+    /// assert!(KEYWORDS.intern("while").is_none());
+    /// ```
+    pub fn intern<T>(&self, key: &T) -> Option<&K>
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_key(key)
+    }
+
     /// Like `get`, but returns both the key and the value.
     pub fn get_entry<T>(&self, key: &T) -> Option<(&K, &V)>
+    where
+        T: Eq + PhfHash + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_internal_index(key).map(|index| {
+            let entry = &self.entries[index];
+            (&entry.0, &entry.1)
+        })
+    }
+
+    /// Returns the index of the slot `key` occupies in the map's internal
+    /// `entries` slice.
+    ///
+    /// The index is fixed when the map is generated and is less than
+    /// `self.len()`, so it may be used to look up a separate array kept
+    /// aligned with the entries of the map without hashing the key again.
+    /// It is unrelated to the order in which the entries were listed.
+    pub fn get_internal_index<T>(&self, key: &T) -> Option<usize>
     where
         T: Eq + PhfHash + ?Sized,
         K: PhfBorrow<T>,
@@ -132,11 +239,10 @@ impl<K, V> Map<K, V> {
             return None;
         } //Prevent panic on empty map
         let hashes = phf_shared::hash(key, &self.key);
-        let index = phf_shared::get_index(&hashes, self.disps, self.entries.len());
-        let entry = &self.entries[index as usize];
-        let b: &T = entry.0.borrow();
+        let index = phf_shared::get_index(&hashes, self.disps, self.entries.len()) as usize;
+        let b: &T = self.entries[index].0.borrow();
         if b == key {
-            Some((&entry.0, &entry.1))
+            Some(index)
         } else {
             None
         }
@@ -151,6 +257,17 @@ impl<K, V> Map<K, V> {
         }
     }
 
+    /// Returns an iterator over clones of the key/value pairs in the map.
+    ///
+    /// The entries of a `Map` are always borrowed from `'static` data, so they
+    /// cannot be moved out of it. Entries are returned in the same order as
+    /// `entries`.
+    pub fn owned_entries(&self) -> IntoEntries<K, V> {
+        IntoEntries {
+            iter: self.entries.iter(),
+        }
+    }
+
     /// Returns an iterator over the keys in the map.
     ///
     /// Keys are returned in an arbitrary but fixed order.
@@ -170,6 +287,48 @@ impl<K, V> Map<K, V> {
     }
 }
 
+/// Implements const lookups for integer-like keys which hash as their
+/// little-endian bytes.
+macro_rules! const_get {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl<V> Map<$t, V> {
+                /// Like `get`, but usable in const contexts.
+                pub const fn get_const(&self, key: $t) -> Option<&V> {
+                    if self.disps.is_empty() {
+                        return None;
+                    } //Prevent panic on empty map
+                    let hashes = phf_shared::hash_int(
+                        key as $u as u64,
+                        core::mem::size_of::<$u>(),
+                        &self.key,
+                    );
+                    let index = phf_shared::get_index(&hashes, self.disps, self.entries.len());
+                    let entry = &self.entries[index as usize];
+                    if entry.0 == key {
+                        Some(&entry.1)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+const_get!(
+    u8 => u8,
+    i8 => u8,
+    u16 => u16,
+    i16 => u16,
+    u32 => u32,
+    i32 => u32,
+    u64 => u64,
+    i64 => u64,
+    char => u32,
+    bool => u8
+);
+
 impl<'a, K, V> IntoIterator for &'a Map<K, V> {
     type Item = (&'a K, &'a V);
     type IntoIter = Entries<'a, K, V>;
@@ -181,7 +340,7 @@ impl<'a, K, V> IntoIterator for &'a Map<K, V> {
 
 /// An iterator over the key/value pairs in a `Map`.
 pub struct Entries<'a, K, V> {
-    iter: slice::Iter<'a, (K, V)>,
+    pub(crate) iter: slice::Iter<'a, (K, V)>,
 }
 
 impl<'a, K, V> Clone for Entries<'a, K, V> {
@@ -227,7 +386,7 @@ impl<'a, K, V> FusedIterator for Entries<'a, K, V> {}
 
 /// An iterator over the keys in a `Map`.
 pub struct Keys<'a, K, V> {
-    iter: Entries<'a, K, V>,
+    pub(crate) iter: Entries<'a, K, V>,
 }
 
 impl<'a, K, V> Clone for Keys<'a, K, V> {
@@ -272,7 +431,7 @@ impl<'a, K, V> FusedIterator for Keys<'a, K, V> {}
 
 /// An iterator over the values in a `Map`.
 pub struct Values<'a, K, V> {
-    iter: Entries<'a, K, V>,
+    pub(crate) iter: Entries<'a, K, V>,
 }
 
 impl<'a, K, V> Clone for Values<'a, K, V> {
@@ -315,6 +474,120 @@ impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
 
 impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
 
+/// An iterator over clones of the key/value pairs in a `Map`.
+pub struct IntoEntries<K: 'static, V: 'static> {
+    iter: slice::Iter<'static, (K, V)>,
+}
+
+impl<K, V> Clone for IntoEntries<K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for IntoEntries<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
+}
+
+impl<K: Clone, V: Clone> Iterator for IntoEntries<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.iter.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K: Clone, V: Clone> DoubleEndedIterator for IntoEntries<K, V> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.iter.next_back().cloned()
+    }
+}
+
+impl<K: Clone, V: Clone> ExactSizeIterator for IntoEntries<K, V> {}
+
+impl<K: Clone, V: Clone> FusedIterator for IntoEntries<K, V> {}
+
+/// An iterator over the values of a sequence of keys looked up in a `Map`.
+///
+/// Created by [`Map::get_many`].
+pub struct GetMany<'a, K: 'static, V: 'static, I> {
+    map: &'a Map<K, V>,
+    keys: I,
+}
+
+impl<'a, K, V, I: Clone> Clone for GetMany<'a, K, V, I> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            keys: self.keys.clone(),
+        }
+    }
+}
+
+impl<'a, K, V, I: fmt::Debug> fmt::Debug for GetMany<'a, K, V, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GetMany").field("keys", &self.keys).finish()
+    }
+}
+
+impl<'a, 'k, K, V, T, I> Iterator for GetMany<'a, K, V, I>
+where
+    T: Eq + PhfHash + ?Sized + 'k,
+    K: PhfBorrow<T>,
+    I: Iterator<Item = &'k T>,
+{
+    type Item = Option<&'a V>;
+
+    fn next(&mut self) -> Option<Option<&'a V>> {
+        self.keys.next().map(|key| self.map.get(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<'a, 'k, K, V, T, I> DoubleEndedIterator for GetMany<'a, K, V, I>
+where
+    T: Eq + PhfHash + ?Sized + 'k,
+    K: PhfBorrow<T>,
+    I: DoubleEndedIterator<Item = &'k T>,
+{
+    fn next_back(&mut self) -> Option<Option<&'a V>> {
+        self.keys.next_back().map(|key| self.map.get(key))
+    }
+}
+
+impl<'a, 'k, K, V, T, I> ExactSizeIterator for GetMany<'a, K, V, I>
+where
+    T: Eq + PhfHash + ?Sized + 'k,
+    K: PhfBorrow<T>,
+    I: ExactSizeIterator<Item = &'k T>,
+{
+}
+
+impl<'a, 'k, K, V, T, I> FusedIterator for GetMany<'a, K, V, I>
+where
+    T: Eq + PhfHash + ?Sized + 'k,
+    K: PhfBorrow<T>,
+    I: FusedIterator<Item = &'k T>,
+{
+}
+
 #[cfg(feature = "serde")]
 impl<K, V> Serialize for Map<K, V>
 where
@@ -5,6 +5,32 @@ use core::iter::IntoIterator;
 use core::ops::Index;
 use core::slice;
 use phf_shared::{self, HashKey, PhfBorrow, PhfHash};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// An integer type which can store the indices of an `OrderedMap`.
+///
+/// `usize` is the default. Narrower types make the table smaller, and must be
+/// able to represent every index below the number of entries.
+pub trait OrderedIndex: Copy + 'static {
+    /// Converts the index to a `usize`.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! ordered_index {
+    ($($t:ty),*) => {
+        $(
+            impl OrderedIndex for $t {
+                #[inline]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+ordered_index!(u8, u16, u32, usize);
 
 /// An order-preserving immutable map constructed at compile time.
 ///
@@ -16,19 +42,23 @@ use phf_shared::{self, HashKey, PhfBorrow, PhfHash};
 /// The fields of this struct are public so that they may be initialized by the
 /// `phf_ordered_map!` macro and code generation. They are subject to change at
 /// any time and should never be accessed directly.
-pub struct OrderedMap<K: 'static, V: 'static> {
+///
+/// The indices into the entries are stored as `I`, which may be narrowed from
+/// the default `usize` to make large maps smaller; see [`OrderedIndex`].
+pub struct OrderedMap<K: 'static, V: 'static, I: 'static = usize> {
     #[doc(hidden)]
     pub key: HashKey,
     #[doc(hidden)]
     pub disps: &'static [(u32, u32)],
     #[doc(hidden)]
-    pub idxs: &'static [usize],
+    pub idxs: &'static [I],
     #[doc(hidden)]
     pub entries: &'static [(K, V)],
 }
 
-impl<K, V> fmt::Debug for OrderedMap<K, V>
+impl<K, V, I> fmt::Debug for OrderedMap<K, V, I>
 where
+    I: OrderedIndex,
     K: fmt::Debug,
     V: fmt::Debug,
 {
@@ -37,20 +67,22 @@ where
     }
 }
 
-impl<'a, K, V, T: ?Sized> Index<&'a T> for OrderedMap<K, V>
+impl<'a, K, V, I, T: ?Sized> Index<&'a T> for OrderedMap<K, V, I>
 where
+    I: OrderedIndex,
     T: Eq + PhfHash,
     K: PhfBorrow<T>,
 {
     type Output = V;
 
     fn index(&self, k: &'a T) -> &V {
-        self.get(k).expect("invalid key")
+        self.get(k).expect("no entry found for key")
     }
 }
 
-impl<K, V> PartialEq for OrderedMap<K, V>
+impl<K, V, I> PartialEq for OrderedMap<K, V, I>
 where
+    I: PartialEq,
     K: PartialEq,
     V: PartialEq,
 {
@@ -62,14 +94,15 @@ where
     }
 }
 
-impl<K, V> Eq for OrderedMap<K, V>
+impl<K, V, I> Eq for OrderedMap<K, V, I>
 where
+    I: Eq,
     K: Eq,
     V: Eq,
 {
 }
 
-impl<K, V> OrderedMap<K, V> {
+impl<K, V, I: OrderedIndex> OrderedMap<K, V, I> {
     /// Returns the number of entries in the `OrderedMap`.
     #[inline]
     pub const fn len(&self) -> usize {
@@ -147,7 +180,7 @@ impl<K, V> OrderedMap<K, V> {
         } //Prevent panic on empty map
         let hashes = phf_shared::hash(key, &self.key);
         let idx_index = phf_shared::get_index(&hashes, self.disps, self.idxs.len());
-        let idx = self.idxs[idx_index as usize];
+        let idx = self.idxs[idx_index as usize].to_usize();
         let entry = &self.entries[idx];
 
         let b: &T = entry.0.borrow();
@@ -186,7 +219,10 @@ impl<K, V> OrderedMap<K, V> {
     }
 }
 
-impl<'a, K, V> IntoIterator for &'a OrderedMap<K, V> {
+impl<'a, K, V, I> IntoIterator for &'a OrderedMap<K, V, I>
+where
+    I: OrderedIndex,
+{
     type Item = (&'a K, &'a V);
     type IntoIter = Entries<'a, K, V>;
 
@@ -197,7 +233,7 @@ impl<'a, K, V> IntoIterator for &'a OrderedMap<K, V> {
 
 /// An iterator over the entries in a `OrderedMap`.
 pub struct Entries<'a, K, V> {
-    iter: slice::Iter<'a, (K, V)>,
+    pub(crate) iter: slice::Iter<'a, (K, V)>,
 }
 
 impl<'a, K, V> Clone for Entries<'a, K, V> {
@@ -243,7 +279,7 @@ impl<'a, K, V> FusedIterator for Entries<'a, K, V> {}
 
 /// An iterator over the keys in a `OrderedMap`.
 pub struct Keys<'a, K, V> {
-    iter: Entries<'a, K, V>,
+    pub(crate) iter: Entries<'a, K, V>,
 }
 
 impl<'a, K, V> Clone for Keys<'a, K, V> {
@@ -288,7 +324,7 @@ impl<'a, K, V> FusedIterator for Keys<'a, K, V> {}
 
 /// An iterator over the values in a `OrderedMap`.
 pub struct Values<'a, K, V> {
-    iter: Entries<'a, K, V>,
+    pub(crate) iter: Entries<'a, K, V>,
 }
 
 impl<'a, K, V> Clone for Values<'a, K, V> {
@@ -330,3 +366,22 @@ impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
 impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
 
 impl<'a, K, V> FusedIterator for Values<'a, K, V> {}
+
+#[cfg(feature = "serde")]
+impl<K, V, I> Serialize for OrderedMap<K, V, I>
+where
+    I: OrderedIndex,
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.entries() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
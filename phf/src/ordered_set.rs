@@ -1,9 +1,12 @@
 //! An order-preserving immutable set constructed at compile time.
-use crate::{ordered_map, OrderedMap, PhfHash};
+use crate::ordered_map::{self, OrderedIndex};
+use crate::{OrderedMap, PhfHash};
 use core::fmt;
 use core::iter::FusedIterator;
 use core::iter::IntoIterator;
 use phf_shared::PhfBorrow;
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeSeq, Serializer};
 
 /// An order-preserving immutable set constructed at compile time.
 ///
@@ -15,32 +18,42 @@ use phf_shared::PhfBorrow;
 /// The fields of this struct are public so that they may be initialized by the
 /// `phf_ordered_set!` macro and code generation. They are subject to change at
 /// any time and should never be accessed directly.
-pub struct OrderedSet<T: 'static> {
+///
+/// As with `OrderedMap`, the index type `I` may be narrowed from the default
+/// `usize`; see [`OrderedIndex`].
+pub struct OrderedSet<T: 'static, I: 'static = usize> {
     #[doc(hidden)]
-    pub map: OrderedMap<T, ()>,
+    pub map: OrderedMap<T, (), I>,
 }
 
-impl<T> fmt::Debug for OrderedSet<T>
+impl<T, I> fmt::Debug for OrderedSet<T, I>
 where
     T: fmt::Debug,
+    I: OrderedIndex,
 {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_set().entries(self).finish()
     }
 }
 
-impl<T> PartialEq for OrderedSet<T>
+impl<T, I> PartialEq for OrderedSet<T, I>
 where
     T: PartialEq,
+    I: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         self.map == other.map
     }
 }
 
-impl<T> Eq for OrderedSet<T> where T: Eq {}
+impl<T, I> Eq for OrderedSet<T, I>
+where
+    T: Eq,
+    I: Eq,
+{
+}
 
-impl<T> OrderedSet<T> {
+impl<T, I: OrderedIndex> OrderedSet<T, I> {
     /// Returns the number of elements in the `OrderedSet`.
     #[inline]
     pub const fn len(&self) -> usize {
@@ -100,30 +113,34 @@ impl<T> OrderedSet<T> {
     }
 }
 
-impl<T> OrderedSet<T>
+impl<T, I> OrderedSet<T, I>
 where
     T: Eq + PhfHash + PhfBorrow<T>,
+    I: OrderedIndex,
 {
     /// Returns true if `other` shares no elements with `self`.
     #[inline]
-    pub fn is_disjoint(&self, other: &OrderedSet<T>) -> bool {
+    pub fn is_disjoint(&self, other: &OrderedSet<T, I>) -> bool {
         !self.iter().any(|value| other.contains(value))
     }
 
     /// Returns true if `other` contains all values in `self`.
     #[inline]
-    pub fn is_subset(&self, other: &OrderedSet<T>) -> bool {
+    pub fn is_subset(&self, other: &OrderedSet<T, I>) -> bool {
         self.iter().all(|value| other.contains(value))
     }
 
     /// Returns true if `self` contains all values in `other`.
     #[inline]
-    pub fn is_superset(&self, other: &OrderedSet<T>) -> bool {
+    pub fn is_superset(&self, other: &OrderedSet<T, I>) -> bool {
         other.is_subset(self)
     }
 }
 
-impl<'a, T> IntoIterator for &'a OrderedSet<T> {
+impl<'a, T, I> IntoIterator for &'a OrderedSet<T, I>
+where
+    I: OrderedIndex,
+{
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -179,3 +196,21 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
 impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
 
 impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+#[cfg(feature = "serde")]
+impl<T, I> Serialize for OrderedSet<T, I>
+where
+    T: Serialize,
+    I: OrderedIndex,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
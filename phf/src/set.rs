@@ -1,14 +1,24 @@
 //! An immutable set constructed at compile time.
+use core::cmp::Ordering;
 use core::fmt;
-use core::iter::FusedIterator;
+use core::hash::{Hash, Hasher};
 use core::iter::IntoIterator;
+use core::iter::{Chain, FusedIterator};
 
 use phf_shared::{PhfBorrow, PhfHash};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeSeq, Serializer};
 
 use crate::{map, Map};
 
 /// An immutable set constructed at compile time.
 ///
+/// A `Set<T>` is a wrapper around a `Map<T, ()>`. The keys of any `Map` can
+/// be treated as a set through [`Map::contains_key`] and [`Map::keys`]
+/// without generating a separate table. When generating code with
+/// `phf_codegen`, a `Set` sharing the hash layout of a generated map can be
+/// printed with `DisplayMap::into_set`.
+///
 /// ## Note
 ///
 /// The fields of this struct are public so that they may be initialized by the
@@ -39,6 +49,46 @@ where
 
 impl<T> Eq for Set<T> where T: Eq {}
 
+/// Compares the values of the sets lexicographically in iteration order.
+///
+/// Sets with the same values in the same order are further ordered by their
+/// hash parameters, keeping the ordering consistent with `Eq`.
+impl<T> PartialOrd for Set<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.iter().partial_cmp(other.iter()) {
+            Some(Ordering::Equal) => Some(self.cmp_hash_params(other)),
+            ord => ord,
+        }
+    }
+}
+
+impl<T> Ord for Set<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter()
+            .cmp(other.iter())
+            .then_with(|| self.cmp_hash_params(other))
+    }
+}
+
+/// Hashes the values of the set in iteration order.
+///
+/// The iteration order depends on the generated table, so the hash is only
+/// stable for a single build of the table.
+impl<T> Hash for Set<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.map.hash(state)
+    }
+}
+
 impl<T> Set<T> {
     /// Returns the number of elements in the `Set`.
     #[inline]
@@ -64,6 +114,17 @@ impl<T> Set<T> {
         self.map.get_key(key)
     }
 
+    /// Interns `key`, returning the set's `'static` instance of it.
+    ///
+    /// This is an alias of [`get_key`](Self::get_key); see [`Map::intern`].
+    pub fn intern<U>(&self, key: &U) -> Option<&T>
+    where
+        U: Eq + PhfHash + ?Sized,
+        T: PhfBorrow<U>,
+    {
+        self.get_key(key)
+    }
+
     /// Returns true if `value` is in the `Set`.
     pub fn contains<U>(&self, value: &U) -> bool
     where
@@ -73,6 +134,31 @@ impl<T> Set<T> {
         self.map.contains_key(value)
     }
 
+    /// Returns true if any of `values` is in the `Set`.
+    ///
+    /// Stops at the first value which is found.
+    pub fn contains_any<'k, U, I>(&self, values: I) -> bool
+    where
+        U: Eq + PhfHash + ?Sized + 'k,
+        T: PhfBorrow<U>,
+        I: IntoIterator<Item = &'k U>,
+    {
+        self.map.contains_any(values)
+    }
+
+    /// Returns an iterator over whether each of `values` is in the `Set`, in
+    /// the order of `values`.
+    pub fn contains_iter<'k, U, I>(&self, values: I) -> ContainsIter<'_, T, I::IntoIter>
+    where
+        U: Eq + PhfHash + ?Sized + 'k,
+        T: PhfBorrow<U>,
+        I: IntoIterator<Item = &'k U>,
+    {
+        ContainsIter {
+            iter: self.map.get_many(values),
+        }
+    }
+
     /// Returns an iterator over the values in the set.
     ///
     /// Values are returned in an arbitrary but fixed order.
@@ -81,6 +167,13 @@ impl<T> Set<T> {
             iter: self.map.keys(),
         }
     }
+
+    fn cmp_hash_params(&self, other: &Self) -> Ordering {
+        self.map
+            .key
+            .cmp(&other.map.key)
+            .then_with(|| self.map.disps.cmp(other.map.disps))
+    }
 }
 
 impl<T> Set<T>
@@ -101,8 +194,65 @@ where
     pub fn is_superset(&self, other: &Set<T>) -> bool {
         other.is_subset(self)
     }
+
+    /// Returns an iterator over the values in `self` which are not in `other`.
+    ///
+    /// Values are returned in an arbitrary but fixed order.
+    pub fn difference<'a>(&'a self, other: &'a Set<T>) -> Difference<'a, T> {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Returns an iterator over the values in both `self` and `other`.
+    ///
+    /// Values are returned in an arbitrary but fixed order.
+    pub fn intersection<'a>(&'a self, other: &'a Set<T>) -> Intersection<'a, T> {
+        Intersection {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Returns an iterator over the values in `self` or `other`, without
+    /// duplicates.
+    ///
+    /// Values are returned in an arbitrary but fixed order.
+    pub fn union<'a>(&'a self, other: &'a Set<T>) -> Union<'a, T> {
+        Union {
+            iter: self.iter().chain(other.difference(self)),
+        }
+    }
+
+    /// Returns an iterator over the values in exactly one of `self` and
+    /// `other`.
+    ///
+    /// Values are returned in an arbitrary but fixed order.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Set<T>) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            iter: self.difference(other).chain(other.difference(self)),
+        }
+    }
+}
+
+/// Implements const lookups for the integer-like types supported by
+/// `Map::get_const`.
+macro_rules! const_contains {
+    ($($t:ty),*) => {
+        $(
+            impl Set<$t> {
+                /// Like `contains`, but usable in const contexts.
+                pub const fn contains_const(&self, value: $t) -> bool {
+                    self.map.get_const(value).is_some()
+                }
+            }
+        )*
+    };
 }
 
+const_contains!(u8, i8, u16, i16, u32, i32, u64, i64, char, bool);
+
 impl<'a, T> IntoIterator for &'a Set<T> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
@@ -156,3 +306,262 @@ impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
 impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
 
 impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+/// An iterator over the values in one `Set` but not another.
+///
+/// Created by [`Set::difference`].
+pub struct Difference<'a, T: 'static> {
+    iter: Iter<'a, T>,
+    other: &'a Set<T>,
+}
+
+impl<'a, T> Clone for Difference<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for Difference<'a, T>
+where
+    T: fmt::Debug + Eq + PhfHash + PhfBorrow<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Difference<'a, T>
+where
+    T: Eq + PhfHash + PhfBorrow<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.iter.find(|value| !other.contains(*value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, T> FusedIterator for Difference<'a, T> where T: Eq + PhfHash + PhfBorrow<T> {}
+
+/// An iterator over the values in both of two `Set`s.
+///
+/// Created by [`Set::intersection`].
+pub struct Intersection<'a, T: 'static> {
+    iter: Iter<'a, T>,
+    other: &'a Set<T>,
+}
+
+impl<'a, T> Clone for Intersection<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            other: self.other,
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for Intersection<'a, T>
+where
+    T: fmt::Debug + Eq + PhfHash + PhfBorrow<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Intersection<'a, T>
+where
+    T: Eq + PhfHash + PhfBorrow<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.iter.find(|value| other.contains(*value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, T> FusedIterator for Intersection<'a, T> where T: Eq + PhfHash + PhfBorrow<T> {}
+
+/// An iterator over the values in either of two `Set`s.
+///
+/// Created by [`Set::union`].
+pub struct Union<'a, T: 'static> {
+    iter: Chain<Iter<'a, T>, Difference<'a, T>>,
+}
+
+impl<'a, T> Clone for Union<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for Union<'a, T>
+where
+    T: fmt::Debug + Eq + PhfHash + PhfBorrow<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Union<'a, T>
+where
+    T: Eq + PhfHash + PhfBorrow<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> FusedIterator for Union<'a, T> where T: Eq + PhfHash + PhfBorrow<T> {}
+
+/// An iterator over the values in exactly one of two `Set`s.
+///
+/// Created by [`Set::symmetric_difference`].
+pub struct SymmetricDifference<'a, T: 'static> {
+    iter: Chain<Difference<'a, T>, Difference<'a, T>>,
+}
+
+impl<'a, T> Clone for SymmetricDifference<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> fmt::Debug for SymmetricDifference<'a, T>
+where
+    T: fmt::Debug + Eq + PhfHash + PhfBorrow<T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for SymmetricDifference<'a, T>
+where
+    T: Eq + PhfHash + PhfBorrow<T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> FusedIterator for SymmetricDifference<'a, T> where T: Eq + PhfHash + PhfBorrow<T> {}
+
+/// An iterator over whether each of a sequence of values is in a `Set`.
+///
+/// Created by [`Set::contains_iter`].
+pub struct ContainsIter<'a, T: 'static, I> {
+    iter: map::GetMany<'a, T, (), I>,
+}
+
+impl<'a, T, I: Clone> Clone for ContainsIter<'a, T, I> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T, I: fmt::Debug> fmt::Debug for ContainsIter<'a, T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ContainsIter")
+            .field("values", &self.iter)
+            .finish()
+    }
+}
+
+impl<'a, 'k, T, U, I> Iterator for ContainsIter<'a, T, I>
+where
+    U: Eq + PhfHash + ?Sized + 'k,
+    T: PhfBorrow<U>,
+    I: Iterator<Item = &'k U>,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        self.iter.next().map(|value| value.is_some())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, 'k, T, U, I> DoubleEndedIterator for ContainsIter<'a, T, I>
+where
+    U: Eq + PhfHash + ?Sized + 'k,
+    T: PhfBorrow<U>,
+    I: DoubleEndedIterator<Item = &'k U>,
+{
+    fn next_back(&mut self) -> Option<bool> {
+        self.iter.next_back().map(|value| value.is_some())
+    }
+}
+
+impl<'a, 'k, T, U, I> ExactSizeIterator for ContainsIter<'a, T, I>
+where
+    U: Eq + PhfHash + ?Sized + 'k,
+    T: PhfBorrow<U>,
+    I: ExactSizeIterator<Item = &'k U>,
+{
+}
+
+impl<'a, 'k, T, U, I> FusedIterator for ContainsIter<'a, T, I>
+where
+    U: Eq + PhfHash + ?Sized + 'k,
+    T: PhfBorrow<U>,
+    I: FusedIterator<Item = &'k U>,
+{
+}
+
+#[cfg(feature = "serde")]
+impl<T> Serialize for Set<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
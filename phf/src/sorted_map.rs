@@ -0,0 +1,185 @@
+//! An immutable map of sorted entries, searched with a binary search.
+use crate::ordered_map::{Entries, Keys, Values};
+use core::fmt;
+use core::iter::IntoIterator;
+use core::ops::Index;
+use phf_shared::PhfBorrow;
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+/// An immutable map of sorted entries, searched with a binary search.
+///
+/// This is an alternative to `Map` for key sets which are too large to
+/// generate a perfect hash function for at build time. Lookups take
+/// logarithmic rather than constant time. Iteration order is the order of the
+/// keys.
+///
+/// ## Note
+///
+/// The fields of this struct are public so that they may be initialized by
+/// code generation. They are subject to change at any time and should never
+/// be accessed directly.
+pub struct SortedMap<K: 'static, V: 'static> {
+    #[doc(hidden)]
+    pub entries: &'static [(K, V)],
+}
+
+impl<K, V> fmt::Debug for SortedMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map().entries(self.entries()).finish()
+    }
+}
+
+impl<'a, K, V, T: ?Sized> Index<&'a T> for SortedMap<K, V>
+where
+    T: Ord,
+    K: PhfBorrow<T>,
+{
+    type Output = V;
+
+    fn index(&self, k: &'a T) -> &V {
+        self.get(k).expect("no entry found for key")
+    }
+}
+
+impl<K, V> Default for SortedMap<K, V> {
+    fn default() -> Self {
+        SortedMap { entries: &[] }
+    }
+}
+
+impl<K, V> PartialEq for SortedMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K, V> Eq for SortedMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> SortedMap<K, V> {
+    /// Returns the number of entries in the `SortedMap`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the `SortedMap` is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Determines if `key` is in the `SortedMap`.
+    pub fn contains_key<T>(&self, key: &T) -> bool
+    where
+        T: Ord + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value that `key` maps to.
+    pub fn get<T>(&self, key: &T) -> Option<&V>
+    where
+        T: Ord + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_entry(key).map(|e| e.1)
+    }
+
+    /// Returns a reference to the map's internal static instance of the given
+    /// key.
+    ///
+    /// This can be useful for interning schemes.
+    pub fn get_key<T>(&self, key: &T) -> Option<&K>
+    where
+        T: Ord + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        self.get_entry(key).map(|e| e.0)
+    }
+
+    /// Like `get`, but returns both the key and the value.
+    pub fn get_entry<T>(&self, key: &T) -> Option<(&K, &V)>
+    where
+        T: Ord + ?Sized,
+        K: PhfBorrow<T>,
+    {
+        let idx = self
+            .entries
+            .binary_search_by(|entry| {
+                let b: &T = entry.0.borrow();
+                b.cmp(key)
+            })
+            .ok()?;
+        let entry = &self.entries[idx];
+        Some((&entry.0, &entry.1))
+    }
+
+    /// Returns an iterator over the key/value pairs in the map.
+    ///
+    /// Entries are returned in the order of their keys.
+    pub fn entries(&self) -> Entries<'_, K, V> {
+        Entries {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys in the map.
+    ///
+    /// Keys are returned in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.entries(),
+        }
+    }
+
+    /// Returns an iterator over the values in the map.
+    ///
+    /// Values are returned in the order of their keys.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.entries(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SortedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Entries<'a, K, V>;
+
+    fn into_iter(self) -> Entries<'a, K, V> {
+        self.entries()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> Serialize for SortedMap<K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.entries() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
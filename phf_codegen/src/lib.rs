@@ -66,9 +66,38 @@
 //! }
 //! ```
 //!
+//! The constructed expressions only use `const`-compatible constructs, so they
+//! may initialize a `const` item just as well as a `static` one. Keys and
+//! values must be constant expressions too, so values are written as
+//! `&'static str` rather than `String` and so on; see the `phf` crate's
+//! documentation on key and value types. The
+//! `as_const_item` methods on the display adapters print such a complete item,
+//! including its type:
+//!
+//! ```rust
+//! let mut builder = phf_codegen::Map::new();
+//! builder.entry("hello", "1").entry("world", "2");
+//!
+//! let source = builder.build().as_const_item("GREETINGS", "&'static str", "u32").to_string();
+//! assert!(source.starts_with("pub const GREETINGS: ::phf::Map<&'static str, u32> = "));
+//! ```
+//!
+//! The `as_static_item` methods print a `static` item instead, and the item's
+//! visibility may be changed with [`DisplayItem::vis`]:
+//!
+//! ```rust
+//! let mut builder = phf_codegen::Set::new();
+//! builder.entry("hello").entry("world");
+//!
+//! let built = builder.build();
+//! let source = built.as_static_item("WORDS", "&'static str").vis("pub(crate)").to_string();
+//! assert!(source.starts_with("pub(crate) static WORDS: ::phf::Set<&'static str> = "));
+//! assert!(source.ends_with(";"));
+//! ```
+//!
 //! ### Byte-String Keys
-//! Byte strings by default produce references to fixed-size arrays; the compiler needs a hint
-//! to coerce them to slices:
+//! Byte strings by default produce references to fixed-size arrays; construct the builder with
+//! `byte_keys` to coerce them to slices:
 //!
 //! build.rs:
 //!
@@ -84,7 +113,7 @@
 //! writeln!(
 //!     &mut file,
 //!      "static KEYWORDS: phf::Map<&'static [u8], Keyword> = \n{};\n",
-//!      phf_codegen::Map::<&[u8]>::new()
+//!      phf_codegen::Map::byte_keys()
 //!          .entry(b"loop", "Keyword::Loop")
 //!          .entry(b"continue", "Keyword::Continue")
 //!          .entry(b"break", "Keyword::Break")
@@ -116,16 +145,22 @@
 //! ## Note
 //!
 //! The compiler's stack will overflow when processing extremely long method
-//! chains (500+ calls). When generating large PHF data structures, consider
-//! looping over the entries or making each call a separate statement:
+//! chains (500+ calls). When generating large PHF data structures, add the
+//! entries from an iterator with `entries_from`, collect them into a builder,
+//! or make each call a separate statement:
 //!
 //! ```rust
 //! let entries = [("hello", "1"), ("world", "2")];
 //!
 //! let mut builder = phf_codegen::Map::new();
-//! for &(key, value) in &entries {
-//!     builder.entry(key, value);
-//! }
+//! builder.entries_from(entries.iter().copied());
+//! // ...
+//! ```
+//!
+//! ```rust
+//! let entries = [("hello", "1"), ("world", "2")];
+//!
+//! let builder = entries.iter().copied().collect::<phf_codegen::Map<_>>();
 //! // ...
 //! ```
 //!
@@ -139,13 +174,23 @@
 #![doc(html_root_url = "https://docs.rs/phf_codegen/0.13.1")]
 #![allow(clippy::new_without_default)]
 
-use phf_shared::{FmtConst, PhfHash};
+use phf_shared::{FmtConst, HashKey, Hashes, PhfHash};
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::hash_map::{self, HashMap};
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::iter;
+use std::vec;
 
-use phf_generator::HashState;
+use phf_generator::{GenError, HashState};
+#[cfg(feature = "proc-macro")]
+use quote::quote;
+
+// The most distinct predicates `Map::build_cfg` accepts, as it prints a map
+// for every combination of them.
+const MAX_CFG_PREDICATES: usize = 12;
 
 struct Delegate<T>(T);
 
@@ -155,361 +200,4594 @@ impl<T: FmtConst> fmt::Display for Delegate<T> {
     }
 }
 
-/// A builder for the `phf::Map` type.
-pub struct Map<'a, K> {
-    keys: Vec<K>,
-    values: Vec<Cow<'a, str>>,
-    path: Cow<'a, str>,
+/// Writes a key, folded to a `str` by `case_insensitive_str` if it is set.
+struct FoldedKey<'a, K>(&'a K, Option<fn(&K) -> String>);
+
+impl<'a, K: FmtConst> fmt::Display for FoldedKey<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(fold) => fold(self.0).as_str().fmt_const(f),
+            None => self.0.fmt_const(f),
+        }
+    }
 }
 
-impl<'a, K: Hash + PhfHash + Eq + FmtConst> Map<'a, K> {
-    /// Creates a new `phf::Map` builder.
-    pub fn new() -> Self {
-        // FIXME rust#27438
-        //
-        // On Windows/MSVC there are major problems with the handling of dllimport.
-        // Here, because downstream build scripts only invoke generics from phf_codegen,
-        // the linker ends up throwing a way a bunch of static symbols we actually need.
-        // This works around the problem, assuming that all clients call `Map::new` by
-        // calling a non-generic function.
-        fn noop_fix_for_27438() {}
-        noop_fix_for_27438();
+/// A key which is written in the constructed source as the given expression,
+/// rather than by its `FmtConst` implementation.
+///
+/// The wrapped key is used to compute the hash, and to detect duplicates, so
+/// the expression must evaluate to a value which hashes the same way at
+/// runtime.
+///
+/// ```rust
+/// use phf_codegen::RawKey;
+///
+/// let mut builder = phf_codegen::Map::new();
+/// builder.entry(RawKey::new("loop", "KEYWORD_LOOP"), "1");
+/// assert!(builder.build().to_string().contains("(KEYWORD_LOOP, 1)"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawKey<'a, K> {
+    key: K,
+    source: Cow<'a, str>,
+}
 
-        Map {
-            keys: vec![],
-            values: vec![],
-            path: Cow::Borrowed("::phf"),
+impl<'a, K> RawKey<'a, K> {
+    /// Creates a key which hashes as `key` and is written as `source`.
+    pub fn new(key: K, source: impl Into<Cow<'a, str>>) -> Self {
+        RawKey {
+            key,
+            source: source.into(),
         }
     }
 
-    /// Set the path to the `phf` crate from the global namespace
-    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.path = path.into();
-        self
+    /// Returns the key used to compute the hash.
+    pub fn key(&self) -> &K {
+        &self.key
     }
 
-    /// Adds an entry to the builder.
-    ///
-    /// `value` will be written exactly as provided in the constructed source.
-    pub fn entry(&mut self, key: K, value: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.keys.push(key);
-        self.values.push(value.into());
-        self
+    /// Returns the expression the key is written as.
+    pub fn source(&self) -> &str {
+        &self.source
     }
+}
 
-    /// Calculate the hash parameters and return a struct implementing
-    /// [`Display`](::std::fmt::Display) which will print the constructed `phf::Map`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if there are any duplicate keys.
-    pub fn build(&self) -> DisplayMap<'_, K> {
-        let mut set = HashSet::new();
-        for key in &self.keys {
-            if !set.insert(key) {
-                panic!("duplicate key `{}`", Delegate(key));
-            }
-        }
+impl<K: PartialEq> PartialEq for RawKey<'_, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
 
-        let state = phf_generator::generate_hash(&self.keys);
+impl<K: Eq> Eq for RawKey<'_, K> {}
 
-        DisplayMap {
-            state,
-            path: &self.path,
-            keys: &self.keys,
-            values: &self.values,
-        }
+impl<K: Hash> Hash for RawKey<'_, K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state)
     }
 }
 
-/// An adapter for printing a [`Map`](Map).
-pub struct DisplayMap<'a, K> {
-    path: &'a str,
-    state: HashState,
-    keys: &'a [K],
-    values: &'a [Cow<'a, str>],
+impl<K: PhfHash> PhfHash for RawKey<'_, K> {
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        self.key.phf_hash(state)
+    }
 }
 
-impl<'a, K: FmtConst + 'a> fmt::Display for DisplayMap<'a, K> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // funky formatting here for nice output
-        write!(
-            f,
-            "{}::Map {{
-    key: {:?},
-    disps: &[",
-            self.path, self.state.key
-        )?;
+impl<K> FmtConst for RawKey<'_, K> {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
 
-        // write map displacements
-        for &(d1, d2) in &self.state.disps {
-            write!(
+/// An error returned by the `try_build` methods of the builders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// A key was added to the builder more than once.
+    DuplicateKey {
+        /// The insertion index of the duplicate entry.
+        index: usize,
+        /// The insertion index of the earlier entry with the same key.
+        first_index: usize,
+        /// The duplicate key, as it would be written in the constructed source.
+        key: String,
+    },
+    /// A value could not be parsed as a Rust expression.
+    ///
+    /// This is only checked when the `validate` feature is enabled and
+    /// validation was requested on the builder.
+    InvalidValue {
+        /// The insertion index of the invalid entry.
+        index: usize,
+        /// The value as provided to the builder.
+        value: String,
+        /// A description of the parse error.
+        message: String,
+    },
+    /// A key could not be written as a `const` expression, as its
+    /// `FmtConst::check_fmt_const` implementation returned an error.
+    UnrepresentableKey {
+        /// The insertion index of the key.
+        index: usize,
+    },
+    /// An entry added by `Map::entry_cfg` was built by a method other than
+    /// `Map::build_cfg`, which would ignore its `cfg` predicate.
+    CfgEntry {
+        /// The insertion index of the entry.
+        index: usize,
+    },
+    /// No PHF could be generated for the keys, such as when a hash function
+    /// given to `build_with_hash_fn` maps too many keys to the same hashes.
+    Unsolvable(GenError),
+    /// More distinct predicates were given to `Map::entry_cfg` than
+    /// `Map::build_cfg` supports.
+    TooManyCfgPredicates {
+        /// The number of distinct predicates.
+        count: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::DuplicateKey {
+                index,
+                first_index,
+                key,
+            } => write!(
                 f,
-                "
-        ({}, {}),",
-                d1, d2
-            )?;
+                "duplicate key `{}` at index {}, first added at index {}",
+                key, index, first_index
+            ),
+            BuildError::InvalidValue {
+                index,
+                value,
+                message,
+            } => write!(
+                f,
+                "invalid value `{}` at index {}: {}",
+                value, index, message
+            ),
+            BuildError::UnrepresentableKey { index } => write!(
+                f,
+                "key at index {} cannot be represented as a const expression",
+                index
+            ),
+            BuildError::CfgEntry { index } => write!(
+                f,
+                "entry at index {} has a cfg predicate and must be built with `build_cfg`",
+                index
+            ),
+            BuildError::Unsolvable(e) => write!(f, "failed to solve PHF: {}", e),
+            BuildError::TooManyCfgPredicates { count } => write!(
+                f,
+                "{} distinct cfg predicates were given, but at most {} are supported",
+                count, MAX_CFG_PREDICATES
+            ),
         }
+    }
+}
 
-        write!(
-            f,
-            "
-    ],
-    entries: &[",
-        )?;
-
-        // write map entries
-        for &idx in &self.state.map {
-            write!(
-                f,
-                "
-        ({}, {}),",
-                Delegate(&self.keys[idx]),
-                &self.values[idx]
-            )?;
+impl BuildError {
+    // Maps the indices of an error for a subset of the entries of a builder,
+    // such as one `build_cfg` variant, back to their insertion indices.
+    fn remap(self, subset: &[usize]) -> Self {
+        match self {
+            BuildError::DuplicateKey {
+                index,
+                first_index,
+                key,
+            } => BuildError::DuplicateKey {
+                index: subset[index],
+                first_index: subset[first_index],
+                key,
+            },
+            BuildError::InvalidValue {
+                index,
+                value,
+                message,
+            } => BuildError::InvalidValue {
+                index: subset[index],
+                value,
+                message,
+            },
+            BuildError::UnrepresentableKey { index } => BuildError::UnrepresentableKey {
+                index: subset[index],
+            },
+            BuildError::CfgEntry { index } => BuildError::CfgEntry {
+                index: subset[index],
+            },
+            BuildError::Unsolvable(e) => BuildError::Unsolvable(e),
+            BuildError::TooManyCfgPredicates { count } => {
+                BuildError::TooManyCfgPredicates { count }
+            }
         }
+    }
+}
 
-        write!(
-            f,
-            "
-    ],
-}}"
-        )
+impl std::error::Error for BuildError {}
+
+fn check_keys<K: FmtConst>(keys: &[K]) -> Result<(), BuildError> {
+    match keys.iter().position(|key| key.check_fmt_const().is_err()) {
+        Some(index) => Err(BuildError::UnrepresentableKey { index }),
+        None => Ok(()),
     }
 }
 
-impl<'a, K, V> FromIterator<(K, V)> for Map<'a, K>
-where
-    K: Hash + PhfHash + Eq + FmtConst,
-    V: Into<Cow<'a, str>>,
-{
-    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let mut map = Map::new();
-        for (key, value) in iter {
-            map.entry(key, value);
+fn check_duplicates<K: Hash + Eq + FmtConst>(keys: &[K]) -> Result<(), BuildError> {
+    let mut indices = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        if let Some(&first_index) = indices.get(key) {
+            return Err(BuildError::DuplicateKey {
+                index,
+                first_index,
+                key: Delegate(key).to_string(),
+            });
         }
-        map
+        indices.insert(key, index);
     }
+    Ok(())
 }
 
-/// A builder for the `phf::Set` type.
-pub struct Set<'a, T> {
-    map: Map<'a, T>,
+// Keys which differ only in case become duplicates once folded.
+fn check_folded_duplicates<K>(keys: &[&K], fold: fn(&K) -> String) -> Result<(), BuildError> {
+    check_duplicates(&keys.iter().map(|key| fold(key)).collect::<Vec<_>>())
 }
 
-impl<'a, T: Hash + PhfHash + Eq + FmtConst> Set<'a, T> {
-    /// Constructs a new `phf::Set` builder.
-    pub fn new() -> Self {
-        Set { map: Map::new() }
+/// Maps the keys of a map being built to the indices of their entries in the
+/// `entries` array of the constructed map.
+///
+/// This is passed to the closures given to `entry_resolved`.
+pub struct EntryIndexResolver<'a, K> {
+    indices: HashMap<&'a K, usize>,
+}
+
+impl<'a, K: Hash + Eq> EntryIndexResolver<'a, K> {
+    fn new(entries: impl Iterator<Item = &'a K>) -> Self {
+        EntryIndexResolver {
+            indices: entries.enumerate().map(|(i, key)| (key, i)).collect(),
+        }
     }
 
-    /// Set the path to the `phf` crate from the global namespace
-    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.map.phf_path(path);
-        self
+    /// Returns the index of the entry for `key` in the `entries` array of the
+    /// constructed map, or `None` if the map does not contain `key`.
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.indices.get(key).copied()
     }
+}
 
-    /// Adds an entry to the builder.
-    pub fn entry(&mut self, entry: T) -> &mut Self {
-        self.map.entry(entry, "()");
-        self
+type ResolveFn<'a, K> = dyn Fn(&EntryIndexResolver<'_, K>) -> String + Send + Sync + 'a;
+
+/// The source text of a value in a map builder.
+enum Value<'a, K> {
+    Source(Cow<'a, str>),
+    Lazy(Box<dyn Fn() -> String + Send + Sync + 'a>),
+    Resolved(Box<ResolveFn<'a, K>>),
+    // An inner map added by `entry_nested`, which is a complete expression
+    // and so never takes the builder's value prefix.
+    Nested(String),
+}
+
+impl<'a, K> Value<'a, K> {
+    fn is_resolved(&self) -> bool {
+        matches!(self, Value::Resolved(_))
     }
 
-    /// Calculate the hash parameters and return a struct implementing
-    /// [`Display`](::std::fmt::Display) which will print the constructed `phf::Set`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if there are any duplicate keys.
-    pub fn build(&self) -> DisplaySet<'_, T> {
-        DisplaySet {
-            inner: self.map.build(),
+    fn to_source(&self, resolver: Option<&EntryIndexResolver<'_, K>>) -> Cow<'_, str> {
+        match self {
+            Value::Source(source) => Cow::Borrowed(source),
+            Value::Lazy(f) => Cow::Owned(f()),
+            Value::Resolved(f) => Cow::Owned(f(resolver.expect("entry indices not resolved"))),
+            Value::Nested(source) => Cow::Borrowed(source),
+        }
+    }
+
+    // Like `to_source`, but with the builder's value prefix applied.
+    fn to_prefixed_source(
+        &self,
+        resolver: Option<&EntryIndexResolver<'_, K>>,
+        prefix: Option<&str>,
+    ) -> Cow<'_, str> {
+        let source = self.to_source(resolver);
+        match prefix {
+            Some(prefix) if !matches!(self, Value::Nested(_)) => {
+                Cow::Owned(format!("{}::{}", prefix, source))
+            }
+            _ => source,
         }
     }
 }
 
-/// An adapter for printing a [`Set`](Set).
-pub struct DisplaySet<'a, T> {
-    inner: DisplayMap<'a, T>,
+#[cfg(feature = "validate")]
+fn validate_values<'v, 'a: 'v, K: 'v>(
+    values: impl IntoIterator<Item = &'v Value<'a, K>>,
+    resolver: Option<&EntryIndexResolver<'_, K>>,
+) -> Result<(), BuildError> {
+    for (index, value) in values.into_iter().enumerate() {
+        let value = value.to_source(resolver);
+        if let Err(e) = syn::parse_str::<syn::Expr>(&value) {
+            return Err(BuildError::InvalidValue {
+                index,
+                value: value.to_string(),
+                message: e.to_string(),
+            });
+        }
+    }
+    Ok(())
 }
 
-impl<'a, T: FmtConst + 'a> fmt::Display for DisplaySet<'a, T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}::Set {{ map: {} }}", self.inner.path, self.inner)
+/// Resolves the entries of a map builder into the keys and values to emit.
+///
+/// With `last_wins`, a repeated key keeps the position at which it was first
+/// inserted but takes the value it was last inserted with.
+fn resolve_entries<'a, K: Hash + Eq + FmtConst>(
+    keys: Vec<&'a K>,
+    values: Vec<&'a Value<'a, K>>,
+    last_wins: bool,
+) -> Result<(Vec<&'a K>, Vec<&'a Value<'a, K>>), BuildError> {
+    check_keys(&keys)?;
+    if !last_wins {
+        check_duplicates(&keys)?;
+        return Ok((keys, values));
+    }
+
+    let mut positions = HashMap::new();
+    let mut unique_keys = vec![];
+    let mut unique_values = vec![];
+    for (key, value) in keys.into_iter().zip(values) {
+        match positions.entry(key) {
+            hash_map::Entry::Occupied(e) => unique_values[*e.get()] = value,
+            hash_map::Entry::Vacant(e) => {
+                e.insert(unique_keys.len());
+                unique_keys.push(key);
+                unique_values.push(value);
+            }
+        }
     }
+    Ok((unique_keys, unique_values))
 }
 
-/// A builder for the `phf::OrderedMap` type.
-pub struct OrderedMap<'a, K> {
-    keys: Vec<K>,
-    values: Vec<Cow<'a, str>>,
-    path: Cow<'a, str>,
+/// Layout options shared by the display adapters.
+#[derive(Clone, Copy)]
+struct Style {
+    compact: bool,
+    indent: usize,
+    rustfmt_skip: bool,
 }
 
-impl<'a, K: Hash + PhfHash + Eq + FmtConst> OrderedMap<'a, K> {
-    /// Constructs a enw `phf::OrderedMap` builder.
-    pub fn new() -> Self {
-        OrderedMap {
-            keys: vec![],
-            values: vec![],
-            path: Cow::Borrowed("::phf"),
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            compact: false,
+            indent: 4,
+            rustfmt_skip: false,
         }
     }
+}
 
-    /// Set the path to the `phf` crate from the global namespace
-    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.path = path.into();
-        self
+impl Style {
+    fn begin_field(self, f: &mut fmt::Formatter<'_>, index: usize, name: &str) -> fmt::Result {
+        if !self.compact {
+            write!(f, "\n{:2$}{}: ", "", name, self.indent)
+        } else if index == 0 {
+            write!(f, " {}: ", name)
+        } else {
+            write!(f, ", {}: ", name)
+        }
     }
 
-    /// Adds an entry to the builder.
-    ///
-    /// `value` will be written exactly as provided in the constructed source.
-    pub fn entry(&mut self, key: K, value: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.keys.push(key);
-        self.values.push(value.into());
-        self
+    fn end_field(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.compact {
+            Ok(())
+        } else {
+            f.write_str(",")
+        }
     }
 
-    /// Calculate the hash parameters and return a struct implementing
-    /// [`Display`](::std::fmt::Display) which will print the constructed
-    /// `phf::OrderedMap`.
-    ///
-    /// # Panics
-    ///
-    /// Panics if there are any duplicate keys.
-    pub fn build(&self) -> DisplayOrderedMap<'_, K> {
-        let mut set = HashSet::new();
-        for key in &self.keys {
-            if !set.insert(key) {
-                panic!("duplicate key `{}`", Delegate(key));
-            }
+    fn item(
+        self,
+        f: &mut fmt::Formatter<'_>,
+        index: usize,
+        item: impl fmt::Display,
+    ) -> fmt::Result {
+        if !self.compact {
+            write!(f, "\n{:2$}{},", "", item, self.indent * 2)
+        } else if index == 0 {
+            write!(f, "{}", item)
+        } else {
+            write!(f, ", {}", item)
         }
+    }
 
-        let state = phf_generator::generate_hash(&self.keys);
+    fn end_list(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.compact {
+            f.write_str("]")
+        } else {
+            write!(f, "\n{:1$}]", "", self.indent)
+        }
+    }
 
-        DisplayOrderedMap {
-            state,
-            path: &self.path,
-            keys: &self.keys,
-            values: &self.values,
+    fn end_struct(self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.compact {
+            f.write_str(" }")
+        } else {
+            f.write_str("\n}")
         }
     }
 }
 
-/// An adapter for printing a [`OrderedMap`](OrderedMap).
-pub struct DisplayOrderedMap<'a, K> {
-    path: &'a str,
-    state: HashState,
-    keys: &'a [K],
-    values: &'a [Cow<'a, str>],
+fn is_identifier(segment: &str) -> bool {
+    let ident = segment.strip_prefix("r#").unwrap_or(segment);
+    let mut chars = ident.chars();
+    match chars.next() {
+        Some(c) if c == '_' || c.is_alphabetic() => {}
+        _ => return false,
+    }
+    ident != "_" && chars.all(|c| c == '_' || c.is_alphanumeric())
 }
 
-impl<'a, K: FmtConst + 'a> fmt::Display for DisplayOrderedMap<'a, K> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}::OrderedMap {{
-    key: {:?},
-    disps: &[",
-            self.path, self.state.key
-        )?;
-        for &(d1, d2) in &self.state.disps {
-            write!(
-                f,
-                "
-        ({}, {}),",
-                d1, d2
-            )?;
-        }
-        write!(
-            f,
-            "
-    ],
-    idxs: &[",
-        )?;
-        for &idx in &self.state.map {
-            write!(
-                f,
-                "
-        {},",
-                idx
-            )?;
+fn join_path_segments(segments: &[&str]) -> String {
+    assert!(!segments.is_empty(), "empty `phf` path");
+
+    let mut path = String::new();
+    for (i, &segment) in segments.iter().enumerate() {
+        let relative = matches!(segment, "crate" | "self" | "super");
+        assert!(
+            is_identifier(segment) && (!relative || segments[..i].iter().all(|&s| s == "super")),
+            "invalid `phf` path segment `{}`",
+            segment
+        );
+        if i > 0 || !relative {
+            path.push_str("::");
         }
-        write!(
-            f,
-            "
-    ],
-    entries: &[",
-        )?;
-        for (key, value) in self.keys.iter().zip(self.values.iter()) {
-            write!(
-                f,
-                "
-        ({}, {}),",
-                Delegate(key),
-                value
-            )?;
+        path.push_str(segment);
+    }
+    path
+}
+
+#[cfg(feature = "proc-macro")]
+fn parse_tokens(source: &str) -> proc_macro2::TokenStream {
+    source
+        .parse()
+        .unwrap_or_else(|e| panic!("unable to tokenize `{}`: {}", source, e))
+}
+
+fn smallest_index_type(len: usize) -> &'static str {
+    // every index is strictly less than `len`
+    match len as u64 {
+        0..=0x100 => "u8",
+        0x101..=0x1_0000 => "u16",
+        0x1_0001..=0x1_0000_0000 => "u32",
+        _ => "usize",
+    }
+}
+
+fn write_header(f: &mut fmt::Formatter<'_>, header: Option<&str>, len: usize) -> fmt::Result {
+    let header = match header {
+        Some(header) => header,
+        None => return Ok(()),
+    };
+    for line in header.lines() {
+        if line.is_empty() {
+            f.write_str("//\n")?;
+        } else {
+            writeln!(f, "// {}", line)?;
         }
-        write!(
-            f,
-            "
-    ],
-}}"
-        )
     }
+    writeln!(
+        f,
+        "// phf_codegen {}, {} entries",
+        env!("CARGO_PKG_VERSION"),
+        len
+    )
 }
 
-/// A builder for the `phf::OrderedSet` type.
-pub struct OrderedSet<'a, T> {
-    map: OrderedMap<'a, T>,
+/// A builder for the `phf::Map` type.
+pub struct Map<'a, K> {
+    keys: Vec<K>,
+    values: Vec<Value<'a, K>>,
+    cfgs: Vec<Option<Cow<'a, str>>>,
+    path: Cow<'a, str>,
+    relative_path: bool,
+    last_wins: bool,
+    style: Style,
+    header: Option<Cow<'a, str>>,
+    len_const: Option<Cow<'a, str>>,
+    value_prefix: Option<Cow<'a, str>>,
+    key_fold: Option<fn(&K) -> String>,
+    static_attrs: Vec<String>,
+    annotate_entries: bool,
+    #[cfg(feature = "validate")]
+    validate: bool,
 }
 
-impl<'a, T: Hash + PhfHash + Eq + FmtConst> OrderedSet<'a, T> {
-    /// Constructs a new `phf::OrderedSet` builder.
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> Map<'a, K> {
+    /// Creates a new `phf::Map` builder.
     pub fn new() -> Self {
-        OrderedSet {
-            map: OrderedMap::new(),
+        Map::with_capacity(0)
+    }
+
+    /// Creates a new `phf::Map` builder with space preallocated for
+    /// `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        // FIXME rust#27438
+        //
+        // On Windows/MSVC there are major problems with the handling of dllimport.
+        // Here, because downstream build scripts only invoke generics from phf_codegen,
+        // the linker ends up throwing a way a bunch of static symbols we actually need.
+        // This works around the problem, assuming that all clients call `Map::new` or
+        // `Map::with_capacity` by calling a non-generic function.
+        fn noop_fix_for_27438() {}
+        noop_fix_for_27438();
+
+        Map {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            cfgs: Vec::with_capacity(capacity),
+            path: Cow::Borrowed("::phf"),
+            relative_path: false,
+            last_wins: false,
+            style: Style::default(),
+            header: None,
+            len_const: None,
+            value_prefix: None,
+            key_fold: None,
+            static_attrs: vec![],
+            annotate_entries: false,
+            #[cfg(feature = "validate")]
+            validate: false,
         }
     }
 
     /// Set the path to the `phf` crate from the global namespace
     pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
-        self.map.phf_path(path);
+        self.path = path.into();
         self
     }
 
-    /// Adds an entry to the builder.
-    pub fn entry(&mut self, entry: T) -> &mut Self {
-        self.map.entry(entry, "()");
+    /// Set the path to the `phf` crate from its individual segments, such as
+    /// `&["my_crate", "phf"]` for `::my_crate::phf`.
+    ///
+    /// The path is absolute unless it starts with `crate`, `self` or `super`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segments` is empty or any segment is not a valid identifier.
+    pub fn phf_path_segments(&mut self, segments: &[&str]) -> &mut Self {
+        self.path = join_path_segments(segments).into();
         self
     }
 
-    /// Calculate the hash parameters and return a struct implementing
-    /// [`Display`](::std::fmt::Display) which will print the constructed
-    /// `phf::OrderedSet`.
-    ///
-    /// # Panics
+    /// Write the path to the `phf` crate without a leading `::`, such as
+    /// `phf::Map` rather than `::phf::Map`, so that it is resolved relative to
+    /// the scope the constructed source is included in.
     ///
-    /// Panics if there are any duplicate keys.
-    pub fn build(&self) -> DisplayOrderedSet<'_, T> {
-        DisplayOrderedSet {
-            inner: self.map.build(),
+    /// This is useful when `phf` is brought into scope by a `use` item rather
+    /// than being available at the root of the global namespace.
+    pub fn relative_path(&mut self) -> &mut Self {
+        self.relative_path = true;
+        self
+    }
+
+    fn display_path(&self) -> &str {
+        if self.relative_path {
+            self.path.strip_prefix("::").unwrap_or(&self.path)
+        } else {
+            &self.path
+        }
+    }
+
+    /// Set a path to prepend to every value in the constructed source, such
+    /// as `crate::Keyword` to write a value of `Loop` as
+    /// `crate::Keyword::Loop`.
+    ///
+    /// This allows the same entries to be emitted for crates with different
+    /// module layouts. Values are otherwise written exactly as provided.
+    pub fn value_prefix(&mut self, prefix: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.value_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Keep the last value added for a repeated key instead of treating it as
+    /// an error when building.
+    ///
+    /// The hash is generated over the deduplicated keys, and each unique key
+    /// keeps the position at which it was first inserted.
+    pub fn dedup_last_wins(&mut self) -> &mut Self {
+        self.last_wins = true;
+        self
+    }
+
+    /// Check that every value parses as a Rust expression when building,
+    /// rather than leaving mistakes to surface as compile errors in the
+    /// generated source.
+    ///
+    /// Requires the `validate` feature.
+    #[cfg(feature = "validate")]
+    pub fn validate_values(&mut self) -> &mut Self {
+        self.validate = true;
+        self
+    }
+
+    /// Print the constructed source on a single line with minimal whitespace
+    /// rather than one entry per line.
+    pub fn compact(&mut self) -> &mut Self {
+        self.style.compact = true;
+        self
+    }
+
+    /// Set the width, in spaces, of one level of indentation in the
+    /// constructed source.
+    ///
+    /// Defaults to 4. Has no effect on [`compact`](Self::compact) output.
+    ///
+    /// With the default width, the constructed source is formatted as
+    /// `rustfmt` would format it, except for small maps, whose `disps` or
+    /// `entries` `rustfmt` joins onto a single line when they fit within its
+    /// `array_width`.
+    pub fn indent(&mut self, spaces: usize) -> &mut Self {
+        self.style.indent = spaces;
+        self
+    }
+
+    /// Prefix the constructed source with a `//` comment block containing
+    /// `text`, followed by a line noting the `phf_codegen` version and the
+    /// number of entries.
+    pub fn header(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.header = Some(text.into());
+        self
+    }
+
+    /// Mark the item printed by `as_const_item` with `#[rustfmt::skip]`, so
+    /// that running `cargo fmt` over an `include!`d file leaves it untouched.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output, as attributes cannot be applied to it.
+    pub fn rustfmt_skip(&mut self) -> &mut Self {
+        self.style.rustfmt_skip = true;
+        self
+    }
+
+    /// Precede the item printed by `as_const_item` with a `const` item named
+    /// `name` holding the number of entries, with the same visibility.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output.
+    pub fn with_len_const(&mut self, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.len_const = Some(name.into());
+        self
+    }
+
+    /// Emit the displacement and entry arrays of the item printed by
+    /// `as_static_item` as separate `static` items, each preceded by the
+    /// attributes in `attrs`, such as `#[link_section = ".rodata.phf"]`.
+    ///
+    /// The arrays are named after the item with `_DISPS` and `_ENTRIES`
+    /// suffixes, are private, and are referenced by the map literal, so they
+    /// must be in scope wherever the item is. Each attribute is written on its
+    /// own line exactly as provided.
+    ///
+    /// ```
+    /// let mut builder = phf_codegen::Map::new();
+    /// builder
+    ///     .static_attrs(&["#[link_section = \".rodata.phf\"]"])
+    ///     .entry("a", "1");
+    /// let source = builder.build().as_static_item("MAP", "&str", "u32").to_string();
+    /// assert!(source.contains("static MAP_DISPS: [(u32, u32); 1] = ["));
+    /// assert!(source.contains("static MAP_ENTRIES: [(&str, u32); 1] = ["));
+    /// assert!(source.contains("entries: &MAP_ENTRIES,"));
+    /// ```
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output, nor on `as_const_item`, as a `const` cannot refer to
+    /// a `static`.
+    pub fn static_attrs(&mut self, attrs: &[&str]) -> &mut Self {
+        self.static_attrs = attrs.iter().map(|attr| attr.to_string()).collect();
+        self
+    }
+
+    /// Follow each entry of the constructed source with a `// key=...`
+    /// comment holding its key as it was added, to ease reviewing the
+    /// generated table.
+    ///
+    /// This has no effect on [`compact`](Self::compact) output, where a line
+    /// comment would hide the rest of the source, nor on the output of
+    /// `to_token_stream` or the backing arrays emitted by `static_attrs`.
+    pub fn annotate_entries(&mut self) -> &mut Self {
+        self.annotate_entries = true;
+        self
+    }
+
+    /// Adds an entry to the builder.
+    ///
+    /// `value` will be written exactly as provided in the constructed source.
+    pub fn entry(&mut self, key: K, value: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Source(value.into()));
+        self.cfgs.push(None);
+        self
+    }
+
+    /// Adds an entry to the builder which is only present when the `cfg`
+    /// predicate holds, such as `unix` or `target_pointer_width = "64"`.
+    ///
+    /// A `phf::Map` is a single table whose hash covers every one of its
+    /// entries, so entries cannot be gated individually. Maps with entries
+    /// added by this method must instead be built with
+    /// [`build_cfg`](Self::build_cfg), which constructs a separate map for
+    /// every combination of the predicates and gates each of them with
+    /// `#[cfg(...)]`. Every other build method returns
+    /// [`BuildError::CfgEntry`] for them.
+    ///
+    /// `value` will be written exactly as provided in the constructed source.
+    pub fn entry_cfg(
+        &mut self,
+        key: K,
+        value: impl Into<Cow<'a, str>>,
+        cfg: impl Into<Cow<'a, str>>,
+    ) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Source(value.into()));
+        self.cfgs.push(Some(cfg.into()));
+        self
+    }
+
+    /// Adds an entry to the builder whose value is produced by `value` only
+    /// when it is needed, rather than being held by the builder.
+    ///
+    /// This saves memory when generating large maps of formulaic values. The
+    /// returned string will be written exactly as provided in the constructed
+    /// source. `value` may be called more than once, such as each time the
+    /// constructed source is printed.
+    pub fn entry_with(
+        &mut self,
+        key: K,
+        value: impl Fn() -> String + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Lazy(Box::new(value)));
+        self.cfgs.push(None);
+        self
+    }
+
+    /// Adds an entry to the builder whose value is the constructed `inner`
+    /// map, written inline as a `phf::Map<key_ty, value_ty>`.
+    ///
+    /// This builds two-level tables without naming and coordinating a
+    /// separate item for every inner map. The value type of the outer map is
+    /// then `phf::Map<key_ty, value_ty>`. Inner maps may themselves contain
+    /// nested maps. A [`value_prefix`](Self::value_prefix) set on this builder
+    /// is not applied to the inner map, whose own values are written as
+    /// configured on its builder.
+    ///
+    /// ```
+    /// let mut en = phf_codegen::Map::new();
+    /// en.entry("GB", "\"United Kingdom\"");
+    /// let mut fr = phf_codegen::Map::new();
+    /// fr.entry("GB", "\"Royaume-Uni\"");
+    ///
+    /// let mut builder = phf_codegen::Map::new();
+    /// builder
+    ///     .entry_nested("en", &en.build(), "&str", "&str")
+    ///     .entry_nested("fr", &fr.build(), "&str", "&str");
+    /// // static NAMES: phf::Map<&str, phf::Map<&str, &str>> = ...;
+    /// // NAMES["fr"]["GB"]
+    /// println!("{}", builder.build().as_static_item("NAMES", "&str", "phf::Map<&str, &str>"));
+    /// ```
+    pub fn entry_nested<K2: FmtConst>(
+        &mut self,
+        key: K,
+        inner: &DisplayMap<'_, K2>,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> &mut Self {
+        let value = format!(
+            "{{ const NESTED: {}::Map<{}, {}> = {}; NESTED }}",
+            inner.path, key_ty, value_ty, inner
+        );
+        self.keys.push(key);
+        self.values.push(Value::Nested(value));
+        self.cfgs.push(None);
+        self
+    }
+
+    /// Adds an entry to the builder whose value is produced by `value` from the
+    /// final layout of the constructed map.
+    ///
+    /// `value` is given an [`EntryIndexResolver`] mapping each key to the index
+    /// of its entry in the constructed `entries` array, which is only known
+    /// once the hash has been generated. This allows tables whose values refer
+    /// to other entries, such as the states of an automaton. The returned
+    /// string will be written exactly as provided in the constructed source.
+    /// `value` may be called more than once, such as each time the
+    /// constructed source is printed.
+    pub fn entry_resolved(
+        &mut self,
+        key: K,
+        value: impl Fn(&EntryIndexResolver<'_, K>) -> String + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Resolved(Box::new(value)));
+        self.cfgs.push(None);
+        self
+    }
+
+    /// Adds every entry of `entries` to the builder.
+    ///
+    /// Prefer this over chaining many calls to `entry`, which can overflow the
+    /// compiler's stack.
+    pub fn entries_from<V, I>(&mut self, entries: I) -> &mut Self
+    where
+        V: Into<Cow<'a, str>>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in entries {
+            self.entry(key, value);
+        }
+        self
+    }
+
+    /// Appends all entries of `other` to the builder.
+    ///
+    /// Keys present in both builders are handled like any other duplicate key
+    /// when building. Only the entries of `other` are taken; its settings are
+    /// discarded.
+    pub fn merge(&mut self, other: Self) -> &mut Self {
+        self.keys.extend(other.keys);
+        self.values.extend(other.values);
+        self.cfgs.extend(other.cfgs);
+        self
+    }
+
+    /// Removes the entries for which `f` returns `false`, keeping the rest in
+    /// insertion order.
+    ///
+    /// `f` is called once per entry, in insertion order, with the key and the
+    /// value as it would be written in the constructed source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are values added by `entry_resolved` and the map cannot
+    /// be built, as those values can only be produced from a built map.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &str) -> bool) -> &mut Self {
+        let keep = self
+            .entries()
+            .map(|(key, value)| f(key, &value))
+            .collect::<Vec<_>>();
+        let mut keep_keys = keep.iter();
+        self.keys.retain(|_| *keep_keys.next().unwrap());
+        let mut keep_values = keep.iter();
+        self.values.retain(|_| *keep_values.next().unwrap());
+        let mut keep_cfgs = keep.iter();
+        self.cfgs.retain(|_| *keep_cfgs.next().unwrap());
+        self
+    }
+
+    /// Returns the number of entries in the builder.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if the builder contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns an iterator over the entries in the builder, in insertion order.
+    ///
+    /// Values added by `entry_with` and `entry_resolved` are produced as they
+    /// are iterated over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are values added by `entry_resolved` and the map cannot
+    /// be built.
+    pub fn entries(&self) -> impl Iterator<Item = (&K, Cow<'_, str>)> + '_ {
+        let resolver = self.resolver();
+        self.keys
+            .iter()
+            .zip(&self.values)
+            .map(move |(key, value)| (key, value.to_source(resolver.as_ref())))
+    }
+
+    // Values added by `entry_resolved` can only be produced from a built map.
+    fn resolver(&self) -> Option<EntryIndexResolver<'_, K>> {
+        if self.values.iter().any(Value::is_resolved) {
+            self.build().resolver
+        } else {
+            None
+        }
+    }
+
+    /// Returns a builder for the inverse of this map, mapping each value back
+    /// to its key.
+    ///
+    /// Values are only known to the builder as source text, so `value_key` is
+    /// called with each of them to produce the corresponding key of the inverse
+    /// map, whose values are this map's keys as they would be written in the
+    /// constructed source. The values must therefore be unique, and the key type
+    /// of the inverse map must implement `PhfHash` and `FmtConst` like any other.
+    ///
+    /// The inverse builder inherits this builder's `phf` path and layout.
+    /// With [`dedup_last_wins`](Self::dedup_last_wins), only the value a key
+    /// was last added with is inverted. Entries added by
+    /// [`entry_cfg`](Self::entry_cfg) are added to the inverse with the same
+    /// predicate, so it must be built with `build_cfg` too.
+    pub fn inverse<V, F>(&self, mut value_key: F) -> Map<'a, V>
+    where
+        V: Hash + PhfHash + Eq + FmtConst,
+        F: FnMut(&str) -> V,
+    {
+        let mut inverse = Map::with_capacity(self.keys.len());
+        inverse.path = self.path.clone();
+        inverse.relative_path = self.relative_path;
+        inverse.style = self.style;
+
+        // Entries for the same key under different predicates are never
+        // present together, so each of them is kept.
+        let mut positions: HashMap<_, usize> = HashMap::new();
+        let mut entries: Vec<(_, &Value<'a, K>, _)> = vec![];
+        for ((key, value), cfg) in self.keys.iter().zip(&self.values).zip(&self.cfgs) {
+            match positions.entry((key, cfg)) {
+                hash_map::Entry::Occupied(e) if self.last_wins => entries[*e.get()].1 = value,
+                e => {
+                    if let hash_map::Entry::Vacant(e) = e {
+                        e.insert(entries.len());
+                    }
+                    entries.push((key, value, cfg));
+                }
+            }
+        }
+
+        let resolver = self.resolver();
+        for (key, value, cfg) in entries {
+            let value = value_key(&value.to_source(resolver.as_ref()));
+            let key = Delegate(key).to_string();
+            match cfg {
+                Some(cfg) => inverse.entry_cfg(value, key, cfg.clone()),
+                None => inverse.entry(value, key),
+            };
+        }
+        inverse
+    }
+
+    /// Calculate the hash parameters and return a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print the constructed `phf::Map`.
+    ///
+    /// The entries are printed in the order of the slots they hash to, which
+    /// depends only on the set of keys and not on the order in which they were
+    /// added to the builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build(&self) -> DisplayMap<'_, K> {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build`, but returns the constructed source as a `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_string(&self) -> String {
+        self.build().to_string()
+    }
+
+    /// Like `build`, but returns an error instead of panicking if there are
+    /// any duplicate keys.
+    pub fn try_build(&self) -> Result<DisplayMap<'_, K>, BuildError> {
+        self.try_build_folded(false)
+    }
+
+    /// Like `build`, but skips checking for duplicate keys, saving the time
+    /// and memory the check takes for large maps whose keys are known to be
+    /// unique.
+    ///
+    /// `dedup_last_wins` has no effect, as every entry is assumed to be
+    /// unique. Duplicate keys never produce a wrong table: the generator
+    /// finds their identical hashes while assigning them to buckets and this
+    /// panics with the same error as `build`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_assume_unique(&self) -> DisplayMap<'_, K> {
+        self.try_build_folded(true)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build`, but returns a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print the constructed map
+    /// as a `phf::ArrayMap`, which owns its entries in arrays rather than
+    /// referring to `&'static` slices.
+    ///
+    /// The whole table is then a single item with no separate backing data,
+    /// at the cost of the lengths of the arrays being part of its type, as
+    /// printed by `DisplayArrayMap::as_const_item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_owned_arrays(&self) -> DisplayArrayMap<'_, K> {
+        self.try_build_owned_arrays()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build_owned_arrays`, but returns an error instead of panicking if
+    /// there are any duplicate keys.
+    pub fn try_build_owned_arrays(&self) -> Result<DisplayArrayMap<'_, K>, BuildError> {
+        self.try_build().map(|inner| DisplayArrayMap { inner })
+    }
+
+    fn try_build_folded(&self, assume_unique: bool) -> Result<DisplayMap<'_, K>, BuildError> {
+        self.check_no_cfgs()?;
+        self.try_build_folded_entries(
+            self.keys.iter().collect(),
+            self.values.iter().collect(),
+            assume_unique,
+        )
+    }
+
+    fn try_build_folded_entries<'s>(
+        &'s self,
+        keys: Vec<&'s K>,
+        values: Vec<&'s Value<'a, K>>,
+        assume_unique: bool,
+    ) -> Result<DisplayMap<'s, K>, BuildError> {
+        match self.key_fold {
+            Some(fold) => self.try_build_entries(
+                keys,
+                values,
+                |key, hash_key| phf_shared::hash(fold(key).as_str(), hash_key),
+                assume_unique,
+            ),
+            None => self.try_build_entries(
+                keys,
+                values,
+                |key, hash_key| phf_shared::hash(key, hash_key),
+                assume_unique,
+            ),
+        }
+    }
+
+    fn check_no_cfgs(&self) -> Result<(), BuildError> {
+        match self.cfgs.iter().position(Option::is_some) {
+            Some(index) => Err(BuildError::CfgEntry { index }),
+            None => Ok(()),
+        }
+    }
+
+    /// Like `build`, but returns a struct which will print a separate
+    /// `phf::Map` for every combination of the predicates given to
+    /// [`entry_cfg`](Self::entry_cfg), each gated with `#[cfg(...)]`.
+    ///
+    /// Each map holds the entries added without a predicate, along with those
+    /// whose predicate holds in its combination. As the maps are complete
+    /// items, they can only be printed by
+    /// [`DisplayCfgMap::as_const_item`] and
+    /// [`DisplayCfgMap::as_static_item`], under the same name, exactly one of
+    /// which is compiled for any target. The number of maps doubles with each
+    /// distinct predicate, so related conditions are best expressed as a
+    /// single predicate, such as `any(unix, target_os = "wasi")`. At most 12
+    /// distinct predicates are supported.
+    ///
+    /// A key may be given an entry under each of several predicates which
+    /// never hold together, such as `unix` and `windows`. The combinations in
+    /// which such entries conflict are printed as a gated `compile_error!`
+    /// rather than a map, so they only fail if they are ever compiled.
+    ///
+    /// ```
+    /// let mut builder = phf_codegen::Map::new();
+    /// builder
+    ///     .entry("sh", "\"/bin/sh\"")
+    ///     .entry_cfg("cmd", "\"cmd.exe\"", "windows");
+    /// // #[cfg(all(not(windows)))]
+    /// // static SHELLS: phf::Map<&str, &str> = ...;
+    /// // #[cfg(all(windows))]
+    /// // static SHELLS: phf::Map<&str, &str> = ...;
+    /// println!("{}", builder.build_cfg().as_static_item("SHELLS", "&str", "&str"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys among the entries added without
+    /// a predicate, or if more than 12 distinct predicates were given.
+    pub fn build_cfg(&self) -> DisplayCfgMap<'_, K> {
+        self.try_build_cfg().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build_cfg`, but returns an error instead of panicking if there
+    /// are any duplicate keys among the entries added without a predicate,
+    /// or if more than 12 distinct predicates were given.
+    pub fn try_build_cfg(&self) -> Result<DisplayCfgMap<'_, K>, BuildError> {
+        let mut predicates: Vec<&str> = vec![];
+        for cfg in self.cfgs.iter().flatten() {
+            if !predicates.contains(&&**cfg) {
+                predicates.push(cfg);
+            }
+        }
+        if predicates.len() > MAX_CFG_PREDICATES {
+            return Err(BuildError::TooManyCfgPredicates {
+                count: predicates.len(),
+            });
+        }
+
+        let mut maps = Vec::with_capacity(1 << predicates.len());
+        for enabled in 0..1usize << predicates.len() {
+            let holds = |bit: usize| enabled & (1 << bit) != 0;
+            let subset: Vec<usize> = (0..self.keys.len())
+                .filter(|&index| match &self.cfgs[index] {
+                    Some(cfg) => holds(predicates.iter().position(|p| p == cfg).unwrap()),
+                    None => true,
+                })
+                .collect();
+            let map = match self
+                .try_build_folded_entries(
+                    subset.iter().map(|&index| &self.keys[index]).collect(),
+                    subset.iter().map(|&index| &self.values[index]).collect(),
+                    false,
+                )
+                .map_err(|e| e.remap(&subset))
+            {
+                Ok(map) => Ok(map),
+                Err(e @ BuildError::DuplicateKey { .. }) if enabled != 0 => Err(e),
+                Err(e) => return Err(e),
+            };
+            let cfg = predicates
+                .iter()
+                .enumerate()
+                .map(|(bit, predicate)| {
+                    if holds(bit) {
+                        predicate.to_string()
+                    } else {
+                        format!("not({})", predicate)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            maps.push((format!("all({})", cfg), map));
+        }
+        Ok(DisplayCfgMap { maps })
+    }
+
+    /// Like `build`, but generates the hash with `hash_fn` rather than
+    /// [`phf_shared::hash`].
+    ///
+    /// `hash_fn` computes the `Hashes` of a key under the given hash key, as
+    /// for [`phf_generator::generate_hash_with_hash_fn`]. The constructed
+    /// `phf::Map` hashes keys with `phf_shared::hash` when looking them up, so
+    /// `hash_fn` must agree with it for the key type used at runtime. This is
+    /// useful when the keys given to the builder are a different type from the
+    /// runtime keys, such as a build-time representation of a custom key type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys, or if `hash_fn` maps too many
+    /// keys to the same hashes for a PHF to be found.
+    pub fn build_with_hash_fn<F>(&self, hash_fn: F) -> DisplayMap<'_, K>
+    where
+        F: Fn(&K, &HashKey) -> Hashes,
+    {
+        self.try_build_with_hash_fn(hash_fn)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build_with_hash_fn`, but returns an error instead of panicking if
+    /// there are any duplicate keys, or if `hash_fn` maps too many keys to the
+    /// same hashes for a PHF to be found.
+    pub fn try_build_with_hash_fn<F>(&self, hash_fn: F) -> Result<DisplayMap<'_, K>, BuildError>
+    where
+        F: Fn(&K, &HashKey) -> Hashes,
+    {
+        self.check_no_cfgs()?;
+        self.try_build_entries(
+            self.keys.iter().collect(),
+            self.values.iter().collect(),
+            hash_fn,
+            false,
+        )
+    }
+
+    fn try_build_entries<'s, F>(
+        &'s self,
+        keys: Vec<&'s K>,
+        values: Vec<&'s Value<'a, K>>,
+        hash_fn: F,
+        assume_unique: bool,
+    ) -> Result<DisplayMap<'s, K>, BuildError>
+    where
+        F: Fn(&K, &HashKey) -> Hashes,
+    {
+        #[cfg(feature = "validate")]
+        if self.validate && !values.iter().any(|value| value.is_resolved()) {
+            validate_values(values.iter().copied(), None)?;
+        }
+
+        let (keys, values) = if assume_unique {
+            check_keys(&keys)?;
+            (keys, values)
+        } else {
+            let (keys, values) = resolve_entries(keys, values, self.last_wins)?;
+            if let Some(fold) = self.key_fold {
+                check_folded_duplicates(&keys, fold)?;
+            }
+            (keys, values)
+        };
+        let state = phf_generator::try_generate_hash_with_hash_fn(&keys, |key, hash_key| {
+            hash_fn(key, hash_key)
+        })
+        .map_err(|e| match e {
+            // Without the check, duplicate keys are only found by their hashes.
+            GenError::DuplicateHashes { index, first_index } if assume_unique => {
+                BuildError::DuplicateKey {
+                    index,
+                    first_index,
+                    key: Delegate(keys[index]).to_string(),
+                }
+            }
+            e => BuildError::Unsolvable(e),
+        })?;
+        let resolver = if values.iter().any(|value| value.is_resolved()) {
+            Some(EntryIndexResolver::new(
+                state.map.iter().map(|&idx| keys[idx]),
+            ))
+        } else {
+            None
+        };
+
+        #[cfg(feature = "validate")]
+        if self.validate && resolver.is_some() {
+            validate_values(values.iter().copied(), resolver.as_ref())?;
         }
+
+        Ok(DisplayMap {
+            state,
+            path: self.display_path(),
+            keys,
+            values,
+            resolver,
+            style: self.style,
+            header: self.header.as_deref(),
+            len_const: self.len_const.as_deref(),
+            value_prefix: self.value_prefix.as_deref(),
+            key_fold: self.key_fold,
+            static_attrs: &self.static_attrs,
+            annotate_entries: self.annotate_entries,
+            unit_values: false,
+        })
     }
 }
 
-/// An adapter for printing a [`OrderedSet`](OrderedSet).
-pub struct DisplayOrderedSet<'a, T> {
-    inner: DisplayOrderedMap<'a, T>,
+/// An adapter for printing a [`Map`](Map).
+pub struct DisplayMap<'a, K> {
+    path: &'a str,
+    state: HashState,
+    keys: Vec<&'a K>,
+    values: Vec<&'a Value<'a, K>>,
+    resolver: Option<EntryIndexResolver<'a, K>>,
+    style: Style,
+    header: Option<&'a str>,
+    len_const: Option<&'a str>,
+    value_prefix: Option<&'a str>,
+    key_fold: Option<fn(&K) -> String>,
+    static_attrs: &'a [String],
+    annotate_entries: bool,
+    unit_values: bool,
 }
 
-impl<'a, T: FmtConst + 'a> fmt::Display for DisplayOrderedSet<'a, T> {
+impl<'a, K: FmtConst + 'a> DisplayMap<'a, K> {
+    /// Writes the constructed `phf::Map` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation, but is streamed into the writer piece by piece rather
+    /// than being rendered into an intermediate `String` first.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns an adapter which will print the constructed `phf::Map` as a
+    /// complete `pub const` item named `name`, with the given key and value
+    /// types.
+    pub fn as_const_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty: format!("{}::Map<{}, {}>", self.path, key_ty, value_ty),
+            rustfmt_skip: self.style.rustfmt_skip,
+            len_const: self.len_const.map(|name| (name, self.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    ///
+    /// If [`Map::static_attrs`] was set, this is preceded by the `static`
+    /// items holding the backing arrays of the map.
+    pub fn as_static_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        let statics = if self.static_attrs.is_empty() {
+            None
+        } else {
+            Some(Box::new(DisplayStatics {
+                map: self,
+                name,
+                key_ty: key_ty.to_string(),
+                value_ty: value_ty.to_string(),
+            }) as Box<dyn ItemStatics + 'b>)
+        };
+        DisplayItem {
+            kind: "static",
+            statics,
+            ..self.as_const_item(name, key_ty, value_ty)
+        }
+    }
+
+    /// Returns the hash parameters of the constructed `phf::Map`.
+    ///
+    /// The indices in `map` refer to the keys in insertion order, with
+    /// repeated keys removed when built with `dedup_last_wins`. This allows
+    /// the state to be checked with `phf_generator::verify` before the map is
+    /// written out, given those keys as the constructed map hashes them:
+    ///
+    /// * With [`Map::case_insensitive_str`], the hash is over the folded keys,
+    ///   so verify the lowercased keys rather than the keys as added.
+    /// * With [`Map::build_with_hash_fn`], the hash is over the `Hashes`
+    ///   computed by the given `hash_fn`, while `verify` hashes with
+    ///   `phf_shared::hash`. Verify against the runtime keys whose
+    ///   `phf_shared::hash` agrees with `hash_fn`, not the build-time keys.
+    pub fn hash_state(&self) -> &HashState {
+        &self.state
+    }
+
+    /// Returns an adapter which will print a `phf::Set` of the keys of the
+    /// constructed `phf::Map`.
+    ///
+    /// The set reuses the hash parameters and layout of the map rather than
+    /// generating them again, and so looks up each key in the same slot.
+    pub fn into_set(self) -> DisplaySet<'a, K> {
+        DisplaySet {
+            inner: DisplayMap {
+                unit_values: true,
+                ..self
+            },
+        }
+    }
+
+    /// Returns the constructed `phf::Map` as tokens, for use in procedural
+    /// macros.
+    ///
+    /// Requires the `proc-macro` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path to the `phf` crate, a key or a value cannot be
+    /// tokenized.
+    #[cfg(feature = "proc-macro")]
+    pub fn to_token_stream(&self) -> proc_macro2::TokenStream {
+        let path = parse_tokens(self.path);
+        let key = self.state.key;
+        let disps = self.state.disps.iter().map(|&(d1, d2)| quote!((#d1, #d2)));
+        let entries = self.state.map.iter().map(|&idx| {
+            let key = parse_tokens(&FoldedKey(self.keys[idx], self.key_fold).to_string());
+            let value = parse_tokens(&self.value_source(idx));
+            quote!((#key, #value))
+        });
+
+        quote! {
+            #path::Map {
+                key: #key,
+                disps: &[#(#disps),*],
+                entries: &[#(#entries),*],
+            }
+        }
+    }
+}
+
+impl<'a, K: FmtConst + 'a> fmt::Display for DisplayMap<'a, K> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}::OrderedSet {{ map: {} }}",
-            self.inner.path, self.inner
+        write_header(f, self.header, self.keys.len())?;
+        self.fmt_body(f)
+    }
+}
+
+impl<'a, K: FmtConst + 'a> DisplayMap<'a, K> {
+    fn value_source(&self, idx: usize) -> Cow<'_, str> {
+        if self.unit_values {
+            Cow::Borrowed("()")
+        } else {
+            self.values[idx].to_prefixed_source(self.resolver.as_ref(), self.value_prefix)
+        }
+    }
+
+    fn entry_source(&self, idx: usize) -> String {
+        format!(
+            "({}, {})",
+            FoldedKey(self.keys[idx], self.key_fold),
+            self.value_source(idx)
         )
     }
+
+    fn fmt_body(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_fields(f, "Map", "&")
+    }
+
+    // Writes a struct literal of type `ty` whose arrays are prefixed with
+    // `array_prefix`, so that a `phf::ArrayMap` shares the layout of a
+    // `phf::Map`.
+    fn fmt_fields(&self, f: &mut fmt::Formatter<'_>, ty: &str, array_prefix: &str) -> fmt::Result {
+        let style = self.style;
+        write!(f, "{}::{} {{", self.path, ty)?;
+
+        style.begin_field(f, 0, "key")?;
+        write!(f, "{:?}", self.state.key)?;
+        style.end_field(f)?;
+
+        // write map displacements
+        style.begin_field(f, 1, "disps")?;
+        write!(f, "{}[", array_prefix)?;
+        for (i, &(d1, d2)) in self.state.disps.iter().enumerate() {
+            style.item(f, i, format_args!("({}, {})", d1, d2))?;
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        // write map entries
+        style.begin_field(f, 2, "entries")?;
+        write!(f, "{}[", array_prefix)?;
+        for (i, &idx) in self.state.map.iter().enumerate() {
+            style.item(f, i, self.entry_source(idx))?;
+            if self.annotate_entries && !style.compact {
+                // A line comment must not span lines of the constructed source.
+                let key = Delegate(self.keys[idx]).to_string().replace('\n', " ");
+                write!(f, " // key={}", key)?;
+            }
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        style.end_struct(f)
+    }
+
+    /// Writes the map literal referring to the backing arrays printed by
+    /// `DisplayStatics`.
+    fn fmt_static_body(&self, f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+        let style = self.style;
+        write!(f, "{}::Map {{", self.path)?;
+
+        style.begin_field(f, 0, "key")?;
+        write!(f, "{:?}", self.state.key)?;
+        style.end_field(f)?;
+
+        style.begin_field(f, 1, "disps")?;
+        write!(f, "&{}_DISPS", name)?;
+        style.end_field(f)?;
+
+        style.begin_field(f, 2, "entries")?;
+        write!(f, "&{}_ENTRIES", name)?;
+        style.end_field(f)?;
+
+        style.end_struct(f)
+    }
+
+    fn fmt_static_array<T: fmt::Display>(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        cfg: Option<&str>,
+        name: &str,
+        ty: &str,
+        items: impl ExactSizeIterator<Item = T>,
+    ) -> fmt::Result {
+        write_cfg(f, cfg)?;
+        for attr in self.static_attrs {
+            writeln!(f, "{}", attr)?;
+        }
+        if self.style.rustfmt_skip {
+            f.write_str("#[rustfmt::skip]\n")?;
+        }
+        write!(f, "static {}: [{}; {}] = [", name, ty, items.len())?;
+        for (i, item) in items.enumerate() {
+            if !self.style.compact {
+                write!(f, "\n{:2$}{},", "", item, self.style.indent)?;
+            } else if i == 0 {
+                write!(f, "{}", item)?;
+            } else {
+                write!(f, ", {}", item)?;
+            }
+        }
+        if self.style.compact {
+            f.write_str("];\n")
+        } else {
+            f.write_str("\n];\n")
+        }
+    }
+}
+
+/// An adapter for printing the maps constructed by [`Map::build_cfg`].
+pub struct DisplayCfgMap<'a, K> {
+    maps: Vec<(String, Result<DisplayMap<'a, K>, BuildError>)>,
+}
+
+impl<'a, K: FmtConst + 'a> DisplayCfgMap<'a, K> {
+    /// Returns an adapter which will print each constructed `phf::Map` as a
+    /// `pub const` item named `name`, gated with `#[cfg(...)]`.
+    ///
+    /// See [`DisplayMap::as_const_item`].
+    pub fn as_const_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayCfgItems<'b, DisplayMap<'a, K>> {
+        self.items(|map| map.as_const_item(name, key_ty, value_ty))
+    }
+
+    /// Like `as_const_item`, but prints `pub static` items instead.
+    ///
+    /// See [`DisplayMap::as_static_item`].
+    pub fn as_static_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayCfgItems<'b, DisplayMap<'a, K>> {
+        self.items(|map| map.as_static_item(name, key_ty, value_ty))
+    }
+
+    fn items<'b>(
+        &'b self,
+        item: impl Fn(&'b DisplayMap<'a, K>) -> DisplayItem<'b, DisplayMap<'a, K>>,
+    ) -> DisplayCfgItems<'b, DisplayMap<'a, K>> {
+        let items = self
+            .maps
+            .iter()
+            .map(|(cfg, map)| match map {
+                Ok(map) => Ok(DisplayItem {
+                    cfg: Some(cfg.clone()),
+                    ..item(map)
+                }),
+                Err(e) => Err((cfg.as_str(), e)),
+            })
+            .collect();
+        DisplayCfgItems { items }
+    }
+}
+
+/// An adapter for printing a set of `cfg` gated items, such as those of a
+/// [`DisplayCfgMap`].
+pub struct DisplayCfgItems<'a, D> {
+    items: Vec<Result<DisplayItem<'a, D>, (&'a str, &'a BuildError)>>,
+}
+
+impl<'a, D> DisplayCfgItems<'a, D> {
+    /// Set the visibility of the items, such as `pub(crate)`.
+    ///
+    /// Defaults to `pub`. An empty string makes the items private.
+    pub fn vis(self, vis: &'a str) -> Self {
+        DisplayCfgItems {
+            items: self
+                .items
+                .into_iter()
+                .map(|item| item.map(|item| item.vis(vis)))
+                .collect(),
+        }
+    }
+}
+
+impl<'a, D: fmt::Display> fmt::Display for DisplayCfgItems<'a, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            match item {
+                Ok(item) => write!(f, "{}", item)?,
+                Err((cfg, e)) => {
+                    write_cfg(f, Some(cfg))?;
+                    write!(f, "compile_error!({:?});", e.to_string())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The backing arrays of a [`DisplayMap`] printed as `static` items, for
+/// [`Map::static_attrs`].
+struct DisplayStatics<'b, 'a, K> {
+    map: &'b DisplayMap<'a, K>,
+    name: &'b str,
+    key_ty: String,
+    value_ty: String,
+}
+
+impl<'b, 'a, K: FmtConst + 'a> ItemStatics for DisplayStatics<'b, 'a, K> {
+    fn fmt_arrays(&self, f: &mut fmt::Formatter<'_>, cfg: Option<&str>) -> fmt::Result {
+        let map = self.map;
+        map.fmt_static_array(
+            f,
+            cfg,
+            &format!("{}_DISPS", self.name),
+            "(u32, u32)",
+            map.state
+                .disps
+                .iter()
+                .map(|&(d1, d2)| format!("({}, {})", d1, d2)),
+        )?;
+        map.fmt_static_array(
+            f,
+            cfg,
+            &format!("{}_ENTRIES", self.name),
+            &format!("({}, {})", self.key_ty, self.value_ty),
+            map.state.map.iter().map(|&idx| map.entry_source(idx)),
+        )
+    }
+
+    fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.map.header, self.map.keys.len())?;
+        self.map.fmt_static_body(f, self.name)
+    }
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst + AsRef<str>> Map<'a, K> {
+    /// Lowercase the keys when building, so that the constructed
+    /// `phf::Map<&'static str, V>` can be used for case-insensitive lookups.
+    ///
+    /// Only ASCII letters are lowercased. Queries must be lowercased the same
+    /// way before looking them up, as the constructed map itself is case
+    /// sensitive; for keys which are not ASCII, or to normalize queries
+    /// automatically, use `UniCase` or `UncasedStr` keys instead. Keys which
+    /// differ only in case are duplicates.
+    ///
+    /// A hash function given to `build_with_hash_fn` is called with the keys
+    /// as they were added.
+    ///
+    /// ```
+    /// let mut builder = phf_codegen::Map::new();
+    /// builder
+    ///     .case_insensitive_str()
+    ///     .entry("Content-Type", "0")
+    ///     .entry("Accept", "1");
+    /// // static HEADERS: phf::Map<&'static str, u32> = ...;
+    /// // HEADERS.get(name.to_ascii_lowercase().as_str())
+    /// assert!(builder.build().to_string().contains("\"content-type\""));
+    /// ```
+    pub fn case_insensitive_str(&mut self) -> &mut Self {
+        self.key_fold = Some(|key| key.as_ref().to_ascii_lowercase());
+        self
+    }
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst + Ord> Map<'a, K> {
+    /// Sort the entries by key and return a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print them as a
+    /// `phf::SortedMap`, searched with a binary search.
+    ///
+    /// No hash is generated, so this is much cheaper than `build` for very
+    /// large maps, at the cost of logarithmic rather than constant time
+    /// lookups. `phf::SortedMap` has the same lookup methods as `phf::Map`.
+    /// The `Ord` implementation of `K` must agree with that of the key type
+    /// used at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_binary_search(&self) -> DisplaySortedMap<'_, K> {
+        self.try_build_binary_search()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build_binary_search`, but returns an error instead of panicking
+    /// if there are any duplicate keys.
+    pub fn try_build_binary_search(&self) -> Result<DisplaySortedMap<'_, K>, BuildError> {
+        self.check_no_cfgs()?;
+        #[cfg(feature = "validate")]
+        if self.validate && !self.values.iter().any(Value::is_resolved) {
+            validate_values(&self.values, None)?;
+        }
+
+        let (keys, values) = resolve_entries(
+            self.keys.iter().collect(),
+            self.values.iter().collect(),
+            self.last_wins,
+        )?;
+        if let Some(fold) = self.key_fold {
+            check_folded_duplicates(&keys, fold)?;
+        }
+        let mut entries: Vec<_> = keys.into_iter().zip(values).collect();
+        match self.key_fold {
+            Some(fold) => entries.sort_by_cached_key(|entry| fold(entry.0)),
+            None => entries.sort_by(|a, b| a.0.cmp(b.0)),
+        }
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let resolver = if values.iter().any(|value| value.is_resolved()) {
+            Some(EntryIndexResolver::new(keys.iter().copied()))
+        } else {
+            None
+        };
+
+        #[cfg(feature = "validate")]
+        if self.validate && resolver.is_some() {
+            validate_values(&self.values, resolver.as_ref())?;
+        }
+
+        Ok(DisplaySortedMap {
+            path: self.display_path(),
+            keys,
+            values,
+            resolver,
+            style: self.style,
+            header: self.header.as_deref(),
+            len_const: self.len_const.as_deref(),
+            value_prefix: self.value_prefix.as_deref(),
+            key_fold: self.key_fold,
+        })
+    }
+}
+
+/// An adapter for printing a [`Map`](Map) as a `phf::SortedMap`.
+pub struct DisplaySortedMap<'a, K> {
+    path: &'a str,
+    keys: Vec<&'a K>,
+    values: Vec<&'a Value<'a, K>>,
+    resolver: Option<EntryIndexResolver<'a, K>>,
+    style: Style,
+    header: Option<&'a str>,
+    len_const: Option<&'a str>,
+    value_prefix: Option<&'a str>,
+    key_fold: Option<fn(&K) -> String>,
+}
+
+impl<'a, K: FmtConst + 'a> DisplaySortedMap<'a, K> {
+    /// Writes the constructed `phf::SortedMap` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns an adapter which will print the constructed `phf::SortedMap` as
+    /// a complete `pub const` item named `name`, with the given key and value
+    /// types.
+    pub fn as_const_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty: format!("{}::SortedMap<{}, {}>", self.path, key_ty, value_ty),
+            rustfmt_skip: self.style.rustfmt_skip,
+            len_const: self.len_const.map(|name| (name, self.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    pub fn as_static_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "static",
+            ..self.as_const_item(name, key_ty, value_ty)
+        }
+    }
+}
+
+impl<'a, K: FmtConst + 'a> fmt::Display for DisplaySortedMap<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.header, self.keys.len())?;
+        let style = self.style;
+        write!(f, "{}::SortedMap {{", self.path)?;
+
+        style.begin_field(f, 0, "entries")?;
+        f.write_str("&[")?;
+        for (i, (key, value)) in self.keys.iter().zip(&self.values).enumerate() {
+            let value = value.to_prefixed_source(self.resolver.as_ref(), self.value_prefix);
+            let key = FoldedKey(*key, self.key_fold);
+            style.item(f, i, format_args!("({}, {})", key, value))?;
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        style.end_struct(f)
+    }
+}
+
+/// An adapter for printing a [`Map`](Map) as a `phf::ArrayMap`.
+pub struct DisplayArrayMap<'a, K> {
+    inner: DisplayMap<'a, K>,
+}
+
+impl<'a, K: FmtConst + 'a> DisplayArrayMap<'a, K> {
+    /// Writes the constructed `phf::ArrayMap` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns an adapter which will print the constructed `phf::ArrayMap` as
+    /// a complete `pub const` item named `name`, with the given key and value
+    /// types and the lengths of its arrays.
+    pub fn as_const_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        let inner = &self.inner;
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty: format!(
+                "{}::ArrayMap<{}, {}, {}, {}>",
+                inner.path,
+                key_ty,
+                value_ty,
+                inner.state.disps.len(),
+                inner.state.map.len()
+            ),
+            rustfmt_skip: inner.style.rustfmt_skip,
+            len_const: inner.len_const.map(|name| (name, inner.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    pub fn as_static_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "static",
+            ..self.as_const_item(name, key_ty, value_ty)
+        }
+    }
+}
+
+impl<'a, K: FmtConst + 'a> fmt::Display for DisplayArrayMap<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.inner.header, self.inner.keys.len())?;
+        self.inner.fmt_fields(f, "ArrayMap", "")
+    }
+}
+
+impl<'a, 'k> Map<'a, &'k [u8]> {
+    /// Constructs a new `phf::Map` builder for byte string keys.
+    ///
+    /// Byte string literals such as `b"loop"` are otherwise inferred as
+    /// references to fixed-size arrays, which do not match a
+    /// `phf::Map<&[u8], V>`. With this constructor they are coerced to
+    /// slices as they are added.
+    pub fn byte_keys() -> Self {
+        Map::new()
+    }
+}
+
+impl<'a, K, V> FromIterator<(K, V)> for Map<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst,
+    V: Into<Cow<'a, str>>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Map::new();
+        for (key, value) in iter {
+            map.entry(key, value);
+        }
+        map
+    }
+}
+
+impl<'a, K, V> Extend<(K, V)> for Map<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst,
+    V: Into<Cow<'a, str>>,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entry(key, value);
+        }
+    }
+}
+
+/// Clones the keys of `map` and borrows its values as source text.
+impl<'a, K> From<&'a BTreeMap<K, String>> for Map<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst + Clone,
+{
+    fn from(map: &'a BTreeMap<K, String>) -> Self {
+        map.iter()
+            .map(|(key, value)| (key.clone(), value.as_str()))
+            .collect()
+    }
+}
+
+/// Clones the keys of `map` and borrows its values as source text.
+impl<'a, K, S> From<&'a HashMap<K, String, S>> for Map<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst + Clone,
+{
+    fn from(map: &'a HashMap<K, String, S>) -> Self {
+        map.iter()
+            .map(|(key, value)| (key.clone(), value.as_str()))
+            .collect()
+    }
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> IntoIterator for Map<'a, K> {
+    type Item = (K, Cow<'a, str>);
+    type IntoIter = IntoIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let resolved = match self.resolver() {
+            Some(resolver) => self
+                .values
+                .iter()
+                .map(|value| match value {
+                    Value::Resolved(_) => Some(value.to_source(Some(&resolver)).into_owned()),
+                    _ => None,
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        IntoIter {
+            iter: self.keys.into_iter().zip(self.values),
+            resolved: resolved.into_iter(),
+        }
+    }
+}
+
+/// An owning iterator over the entries of a [`Map`] or [`OrderedMap`]
+/// builder.
+///
+/// Values added by `entry_with` are produced as they are iterated over, while
+/// those added by `entry_resolved` are produced when the iterator is created.
+pub struct IntoIter<'a, K> {
+    iter: iter::Zip<vec::IntoIter<K>, vec::IntoIter<Value<'a, K>>>,
+    // empty unless there are values added by `entry_resolved`
+    resolved: vec::IntoIter<Option<String>>,
+}
+
+impl<'a, K> IntoIter<'a, K> {
+    fn source(value: Value<'a, K>, resolved: Option<Option<String>>) -> Cow<'a, str> {
+        match value {
+            Value::Source(source) => source,
+            Value::Lazy(f) => Cow::Owned(f()),
+            Value::Resolved(_) => Cow::Owned(resolved.flatten().unwrap()),
+            Value::Nested(source) => Cow::Owned(source),
+        }
+    }
+}
+
+impl<'a, K> Iterator for IntoIter<'a, K> {
+    type Item = (K, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        Some((key, Self::source(value, self.resolved.next())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K> DoubleEndedIterator for IntoIter<'a, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next_back()?;
+        Some((key, Self::source(value, self.resolved.next_back())))
+    }
+}
+
+impl<'a, K> ExactSizeIterator for IntoIter<'a, K> {}
+
+/// A builder for the `phf::Set` type.
+pub struct Set<'a, T> {
+    map: Map<'a, T>,
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> Set<'a, T> {
+    /// Constructs a new `phf::Set` builder.
+    pub fn new() -> Self {
+        Set { map: Map::new() }
+    }
+
+    /// Constructs a new `phf::Set` builder with space preallocated for
+    /// `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Set {
+            map: Map::with_capacity(capacity),
+        }
+    }
+
+    /// Set the path to the `phf` crate from the global namespace
+    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.phf_path(path);
+        self
+    }
+
+    /// Set the path to the `phf` crate from its individual segments, such as
+    /// `&["my_crate", "phf"]` for `::my_crate::phf`.
+    ///
+    /// The path is absolute unless it starts with `crate`, `self` or `super`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segments` is empty or any segment is not a valid identifier.
+    pub fn phf_path_segments(&mut self, segments: &[&str]) -> &mut Self {
+        self.map.phf_path_segments(segments);
+        self
+    }
+
+    /// Write the path to the `phf` crate without a leading `::`, such as
+    /// `phf::Map` rather than `::phf::Map`, so that it is resolved relative to
+    /// the scope the constructed source is included in.
+    ///
+    /// This is useful when `phf` is brought into scope by a `use` item rather
+    /// than being available at the root of the global namespace.
+    pub fn relative_path(&mut self) -> &mut Self {
+        self.map.relative_path();
+        self
+    }
+
+    /// Print the constructed source on a single line with minimal whitespace
+    /// rather than one entry per line.
+    pub fn compact(&mut self) -> &mut Self {
+        self.map.compact();
+        self
+    }
+
+    /// Set the width, in spaces, of one level of indentation in the
+    /// constructed source.
+    ///
+    /// Defaults to 4. Has no effect on [`compact`](Self::compact) output.
+    pub fn indent(&mut self, spaces: usize) -> &mut Self {
+        self.map.indent(spaces);
+        self
+    }
+
+    /// Prefix the constructed source with a `//` comment block containing
+    /// `text`, followed by a line noting the `phf_codegen` version and the
+    /// number of entries.
+    pub fn header(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.header(text);
+        self
+    }
+
+    /// Mark the item printed by `as_const_item` with `#[rustfmt::skip]`, so
+    /// that running `cargo fmt` over an `include!`d file leaves it untouched.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output, as attributes cannot be applied to it.
+    pub fn rustfmt_skip(&mut self) -> &mut Self {
+        self.map.rustfmt_skip();
+        self
+    }
+
+    /// Precede the item printed by `as_const_item` with a `const` item named
+    /// `name` holding the number of entries, with the same visibility.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output.
+    pub fn with_len_const(&mut self, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.with_len_const(name);
+        self
+    }
+
+    /// Adds an entry to the builder.
+    pub fn entry(&mut self, entry: T) -> &mut Self {
+        self.map.entry(entry, "()");
+        self
+    }
+
+    /// Adds every entry of `entries` to the builder.
+    ///
+    /// Prefer this over chaining many calls to `entry`, which can overflow the
+    /// compiler's stack.
+    pub fn entries_from<I: IntoIterator<Item = T>>(&mut self, entries: I) -> &mut Self {
+        for entry in entries {
+            self.entry(entry);
+        }
+        self
+    }
+
+    /// Appends all entries of `other` to the builder.
+    ///
+    /// Entries present in both builders are handled like any other duplicate
+    /// entry when building. Only the entries of `other` are taken; its
+    /// settings are discarded.
+    pub fn merge(&mut self, other: Self) -> &mut Self {
+        self.map.merge(other.map);
+        self
+    }
+
+    /// Returns the number of entries in the builder.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the builder contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the entries in the builder, in insertion order.
+    pub fn entries(&self) -> impl Iterator<Item = &T> + '_ {
+        self.map.entries().map(|(k, _)| k)
+    }
+
+    /// Calculate the hash parameters and return a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print the constructed `phf::Set`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build(&self) -> DisplaySet<'_, T> {
+        DisplaySet {
+            inner: self.map.build(),
+        }
+    }
+
+    /// Like `build`, but returns the constructed source as a `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_string(&self) -> String {
+        self.build().to_string()
+    }
+
+    /// Like `build`, but returns an error instead of panicking if there are
+    /// any duplicate entries.
+    pub fn try_build(&self) -> Result<DisplaySet<'_, T>, BuildError> {
+        Ok(DisplaySet {
+            inner: self.map.try_build()?,
+        })
+    }
+
+    /// Like `build`, but generates the hash with `hash_fn` rather than
+    /// [`phf_shared::hash`]; see `Map::build_with_hash_fn`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate entries, or if `hash_fn` maps too
+    /// many entries to the same hashes for a PHF to be found.
+    pub fn build_with_hash_fn<F>(&self, hash_fn: F) -> DisplaySet<'_, T>
+    where
+        F: Fn(&T, &HashKey) -> Hashes,
+    {
+        DisplaySet {
+            inner: self.map.build_with_hash_fn(hash_fn),
+        }
+    }
+}
+
+impl<'a, 'k> Set<'a, &'k [u8]> {
+    /// Constructs a new `phf::Set` builder for byte string entries.
+    ///
+    /// Byte string literals such as `b"loop"` are otherwise inferred as
+    /// references to fixed-size arrays, which do not match a
+    /// `phf::Set<&[u8]>`. With this constructor they are coerced to
+    /// slices as they are added.
+    pub fn byte_keys() -> Self {
+        Set::new()
+    }
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> FromIterator<T> for Set<'a, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Set::new();
+        for entry in iter {
+            set.entry(entry);
+        }
+        set
+    }
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> Extend<T> for Set<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for entry in iter {
+            self.entry(entry);
+        }
+    }
+}
+
+/// An adapter for printing a [`Set`](Set).
+pub struct DisplaySet<'a, T> {
+    inner: DisplayMap<'a, T>,
+}
+
+impl<'a, T: FmtConst + 'a> DisplaySet<'a, T> {
+    /// Writes the constructed `phf::Set` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation, but is streamed into the writer piece by piece rather
+    /// than being rendered into an intermediate `String` first.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns an adapter which will print the constructed `phf::Set` as a
+    /// complete `pub const` item named `name`, with the given element type.
+    pub fn as_const_item<'b>(&'b self, name: &'b str, ty: &str) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty: format!("{}::Set<{}>", self.inner.path, ty),
+            rustfmt_skip: self.inner.style.rustfmt_skip,
+            len_const: self
+                .inner
+                .len_const
+                .map(|name| (name, self.inner.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    pub fn as_static_item<'b>(&'b self, name: &'b str, ty: &str) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "static",
+            ..self.as_const_item(name, ty)
+        }
+    }
+
+    /// Returns the constructed `phf::Set` as tokens, for use in procedural
+    /// macros.
+    ///
+    /// Requires the `proc-macro` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path to the `phf` crate or an entry cannot be tokenized.
+    #[cfg(feature = "proc-macro")]
+    pub fn to_token_stream(&self) -> proc_macro2::TokenStream {
+        let path = parse_tokens(self.inner.path);
+        let map = self.inner.to_token_stream();
+        quote!(#path::Set { map: #map })
+    }
+}
+
+impl<'a, T: FmtConst + 'a> fmt::Display for DisplaySet<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.inner.header, self.inner.keys.len())?;
+        write!(f, "{}::Set {{ map: ", self.inner.path)?;
+        self.inner.fmt_body(f)?;
+        f.write_str(" }")
+    }
+}
+
+/// A builder for the `phf::OrderedMap` type.
+pub struct OrderedMap<'a, K> {
+    keys: Vec<K>,
+    values: Vec<Value<'a, K>>,
+    path: Cow<'a, str>,
+    relative_path: bool,
+    last_wins: bool,
+    style: Style,
+    header: Option<Cow<'a, str>>,
+    len_const: Option<Cow<'a, str>>,
+    value_prefix: Option<Cow<'a, str>>,
+    narrow_indices: bool,
+    #[cfg(feature = "validate")]
+    validate: bool,
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> OrderedMap<'a, K> {
+    /// Constructs a enw `phf::OrderedMap` builder.
+    pub fn new() -> Self {
+        OrderedMap::with_capacity(0)
+    }
+
+    /// Constructs a new `phf::OrderedMap` builder with space preallocated for
+    /// `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        OrderedMap {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            path: Cow::Borrowed("::phf"),
+            relative_path: false,
+            last_wins: false,
+            style: Style::default(),
+            header: None,
+            len_const: None,
+            value_prefix: None,
+            narrow_indices: false,
+            #[cfg(feature = "validate")]
+            validate: false,
+        }
+    }
+
+    /// Set the path to the `phf` crate from the global namespace
+    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set the path to the `phf` crate from its individual segments, such as
+    /// `&["my_crate", "phf"]` for `::my_crate::phf`.
+    ///
+    /// The path is absolute unless it starts with `crate`, `self` or `super`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segments` is empty or any segment is not a valid identifier.
+    pub fn phf_path_segments(&mut self, segments: &[&str]) -> &mut Self {
+        self.path = join_path_segments(segments).into();
+        self
+    }
+
+    /// Write the path to the `phf` crate without a leading `::`, such as
+    /// `phf::Map` rather than `::phf::Map`, so that it is resolved relative to
+    /// the scope the constructed source is included in.
+    ///
+    /// This is useful when `phf` is brought into scope by a `use` item rather
+    /// than being available at the root of the global namespace.
+    pub fn relative_path(&mut self) -> &mut Self {
+        self.relative_path = true;
+        self
+    }
+
+    fn display_path(&self) -> &str {
+        if self.relative_path {
+            self.path.strip_prefix("::").unwrap_or(&self.path)
+        } else {
+            &self.path
+        }
+    }
+
+    /// Set a path to prepend to every value in the constructed source, such
+    /// as `crate::Keyword` to write a value of `Loop` as
+    /// `crate::Keyword::Loop`.
+    ///
+    /// This allows the same entries to be emitted for crates with different
+    /// module layouts. Values are otherwise written exactly as provided.
+    pub fn value_prefix(&mut self, prefix: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.value_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Keep the last value added for a repeated key instead of treating it as
+    /// an error when building.
+    ///
+    /// The hash is generated over the deduplicated keys, and each unique key
+    /// keeps the position at which it was first inserted.
+    pub fn dedup_last_wins(&mut self) -> &mut Self {
+        self.last_wins = true;
+        self
+    }
+
+    /// Check that every value parses as a Rust expression when building,
+    /// rather than leaving mistakes to surface as compile errors in the
+    /// generated source.
+    ///
+    /// Requires the `validate` feature.
+    #[cfg(feature = "validate")]
+    pub fn validate_values(&mut self) -> &mut Self {
+        self.validate = true;
+        self
+    }
+
+    /// Print the constructed source on a single line with minimal whitespace
+    /// rather than one entry per line.
+    pub fn compact(&mut self) -> &mut Self {
+        self.style.compact = true;
+        self
+    }
+
+    /// Set the width, in spaces, of one level of indentation in the
+    /// constructed source.
+    ///
+    /// Defaults to 4. Has no effect on [`compact`](Self::compact) output.
+    pub fn indent(&mut self, spaces: usize) -> &mut Self {
+        self.style.indent = spaces;
+        self
+    }
+
+    /// Prefix the constructed source with a `//` comment block containing
+    /// `text`, followed by a line noting the `phf_codegen` version and the
+    /// number of entries.
+    pub fn header(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.header = Some(text.into());
+        self
+    }
+
+    /// Mark the item printed by `as_const_item` with `#[rustfmt::skip]`, so
+    /// that running `cargo fmt` over an `include!`d file leaves it untouched.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output, as attributes cannot be applied to it.
+    pub fn rustfmt_skip(&mut self) -> &mut Self {
+        self.style.rustfmt_skip = true;
+        self
+    }
+
+    /// Precede the item printed by `as_const_item` with a `const` item named
+    /// `name` holding the number of entries, with the same visibility.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output.
+    pub fn with_len_const(&mut self, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.len_const = Some(name.into());
+        self
+    }
+
+    /// Store the indices of the constructed `phf::OrderedMap` using the
+    /// smallest of `u8`, `u16`, `u32` and `usize` able to hold them, rather
+    /// than always using `usize`.
+    ///
+    /// The map's type must then name the index type, such as
+    /// `phf::OrderedMap<K, V, u16>`. It is available from
+    /// [`DisplayOrderedMap::index_type`] and is included by `as_const_item`.
+    pub fn narrow_indices(&mut self) -> &mut Self {
+        self.narrow_indices = true;
+        self
+    }
+
+    /// Adds an entry to the builder.
+    ///
+    /// `value` will be written exactly as provided in the constructed source.
+    pub fn entry(&mut self, key: K, value: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Source(value.into()));
+        self
+    }
+
+    /// Adds an entry to the builder whose value is produced by `value` only
+    /// when it is needed, rather than being held by the builder.
+    ///
+    /// This saves memory when generating large maps of formulaic values. The
+    /// returned string will be written exactly as provided in the constructed
+    /// source. `value` may be called more than once, such as each time the
+    /// constructed source is printed.
+    pub fn entry_with(
+        &mut self,
+        key: K,
+        value: impl Fn() -> String + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Lazy(Box::new(value)));
+        self
+    }
+
+    /// Adds an entry to the builder whose value is produced by `value` from the
+    /// final layout of the constructed map.
+    ///
+    /// `value` is given an [`EntryIndexResolver`] mapping each key to the index
+    /// of its entry in the constructed `entries` array, which for an ordered
+    /// map is its position among the deduplicated keys. The returned string
+    /// will be written exactly as provided in the constructed source. `value`
+    /// may be called more than once, such as each time the constructed source
+    /// is printed.
+    pub fn entry_resolved(
+        &mut self,
+        key: K,
+        value: impl Fn(&EntryIndexResolver<'_, K>) -> String + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.keys.push(key);
+        self.values.push(Value::Resolved(Box::new(value)));
+        self
+    }
+
+    /// Adds every entry of `entries` to the builder.
+    ///
+    /// Prefer this over chaining many calls to `entry`, which can overflow the
+    /// compiler's stack.
+    pub fn entries_from<V, I>(&mut self, entries: I) -> &mut Self
+    where
+        V: Into<Cow<'a, str>>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in entries {
+            self.entry(key, value);
+        }
+        self
+    }
+
+    /// Appends all entries of `other` to the builder.
+    ///
+    /// Keys present in both builders are handled like any other duplicate key
+    /// when building. Only the entries of `other` are taken; its settings are
+    /// discarded.
+    pub fn merge(&mut self, other: Self) -> &mut Self {
+        self.keys.extend(other.keys);
+        self.values.extend(other.values);
+        self
+    }
+
+    /// Returns the number of entries in the builder.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if the builder contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the index of `key` among the entries of the constructed
+    /// `phf::OrderedMap`, as returned by its `get_index` method.
+    ///
+    /// This is the position at which `key` was first inserted, not counting
+    /// repeated keys when they are deduplicated with `dedup_last_wins`. It is
+    /// computed by a linear search over the entries.
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        if !self.last_wins {
+            return self.keys.iter().position(|k| k == key);
+        }
+        let mut seen = HashSet::new();
+        self.keys
+            .iter()
+            .filter(|k| seen.insert(*k))
+            .position(|k| k == key)
+    }
+
+    /// Returns an iterator over the entries in the builder, in insertion order.
+    ///
+    /// Values added by `entry_with` and `entry_resolved` are produced as they
+    /// are iterated over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are values added by `entry_resolved` and the map cannot
+    /// be built.
+    pub fn entries(&self) -> impl Iterator<Item = (&K, Cow<'_, str>)> + '_ {
+        let resolver = self.resolver();
+        self.keys
+            .iter()
+            .zip(&self.values)
+            .map(move |(key, value)| (key, value.to_source(resolver.as_ref())))
+    }
+
+    // Values added by `entry_resolved` can only be produced from a built map.
+    fn resolver(&self) -> Option<EntryIndexResolver<'_, K>> {
+        if self.values.iter().any(Value::is_resolved) {
+            self.build().resolver
+        } else {
+            None
+        }
+    }
+
+    /// Calculate the hash parameters and return a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print the constructed
+    /// `phf::OrderedMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build(&self) -> DisplayOrderedMap<'_, K> {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build`, but returns the constructed source as a `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_string(&self) -> String {
+        self.build().to_string()
+    }
+
+    /// Like `build`, but returns an error instead of panicking if there are
+    /// any duplicate keys.
+    pub fn try_build(&self) -> Result<DisplayOrderedMap<'_, K>, BuildError> {
+        self.try_build_with_hash_fn(|key, hash_key| phf_shared::hash(key, hash_key))
+    }
+
+    /// Like `build`, but generates the hash with `hash_fn` rather than
+    /// [`phf_shared::hash`].
+    ///
+    /// `hash_fn` computes the `Hashes` of a key under the given hash key, as
+    /// for [`phf_generator::generate_hash_with_hash_fn`]. The constructed
+    /// `phf::OrderedMap` hashes keys with `phf_shared::hash` when looking them up, so
+    /// `hash_fn` must agree with it for the key type used at runtime. This is
+    /// useful when the keys given to the builder are a different type from the
+    /// runtime keys, such as a build-time representation of a custom key type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys, or if `hash_fn` maps too many
+    /// keys to the same hashes for a PHF to be found.
+    pub fn build_with_hash_fn<F>(&self, hash_fn: F) -> DisplayOrderedMap<'_, K>
+    where
+        F: Fn(&K, &HashKey) -> Hashes,
+    {
+        self.try_build_with_hash_fn(hash_fn)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build_with_hash_fn`, but returns an error instead of panicking if
+    /// there are any duplicate keys, or if `hash_fn` maps too many keys to the
+    /// same hashes for a PHF to be found.
+    pub fn try_build_with_hash_fn<F>(
+        &self,
+        hash_fn: F,
+    ) -> Result<DisplayOrderedMap<'_, K>, BuildError>
+    where
+        F: Fn(&K, &HashKey) -> Hashes,
+    {
+        #[cfg(feature = "validate")]
+        if self.validate && !self.values.iter().any(Value::is_resolved) {
+            validate_values(&self.values, None)?;
+        }
+
+        let (keys, values) = resolve_entries(
+            self.keys.iter().collect(),
+            self.values.iter().collect(),
+            self.last_wins,
+        )?;
+        let state = phf_generator::try_generate_hash_with_hash_fn(&keys, |key, hash_key| {
+            hash_fn(key, hash_key)
+        })
+        .map_err(BuildError::Unsolvable)?;
+        let resolver = if values.iter().any(|value| value.is_resolved()) {
+            Some(EntryIndexResolver::new(keys.iter().copied()))
+        } else {
+            None
+        };
+
+        #[cfg(feature = "validate")]
+        if self.validate && resolver.is_some() {
+            validate_values(&self.values, resolver.as_ref())?;
+        }
+
+        let index_type = if self.narrow_indices {
+            Some(smallest_index_type(keys.len()))
+        } else {
+            None
+        };
+
+        Ok(DisplayOrderedMap {
+            state,
+            path: self.display_path(),
+            keys,
+            values,
+            resolver,
+            style: self.style,
+            header: self.header.as_deref(),
+            len_const: self.len_const.as_deref(),
+            value_prefix: self.value_prefix.as_deref(),
+            index_type,
+        })
+    }
+}
+
+/// An adapter for printing a [`OrderedMap`](OrderedMap).
+pub struct DisplayOrderedMap<'a, K> {
+    path: &'a str,
+    state: HashState,
+    keys: Vec<&'a K>,
+    values: Vec<&'a Value<'a, K>>,
+    resolver: Option<EntryIndexResolver<'a, K>>,
+    style: Style,
+    header: Option<&'a str>,
+    len_const: Option<&'a str>,
+    value_prefix: Option<&'a str>,
+    index_type: Option<&'static str>,
+}
+
+impl<'a, K: FmtConst + 'a> DisplayOrderedMap<'a, K> {
+    /// Writes the constructed `phf::OrderedMap` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation, but is streamed into the writer piece by piece rather
+    /// than being rendered into an intermediate `String` first.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns the type used to store the indices of the constructed
+    /// `phf::OrderedMap`.
+    ///
+    /// This is `usize` unless [`OrderedMap::narrow_indices`] was called.
+    pub fn index_type(&self) -> &'static str {
+        self.index_type.unwrap_or("usize")
+    }
+
+    /// Returns an adapter which will print the constructed `phf::OrderedMap`
+    /// as a complete `pub const` item named `name`, with the given key and
+    /// value types.
+    pub fn as_const_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        let ty = match self.index_type {
+            Some(index_ty) => format!(
+                "{}::OrderedMap<{}, {}, {}>",
+                self.path, key_ty, value_ty, index_ty
+            ),
+            None => format!("{}::OrderedMap<{}, {}>", self.path, key_ty, value_ty),
+        };
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty,
+            rustfmt_skip: self.style.rustfmt_skip,
+            len_const: self.len_const.map(|name| (name, self.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    pub fn as_static_item<'b>(
+        &'b self,
+        name: &'b str,
+        key_ty: &str,
+        value_ty: &str,
+    ) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "static",
+            ..self.as_const_item(name, key_ty, value_ty)
+        }
+    }
+
+    /// Returns the constructed `phf::OrderedMap` as tokens, for use in
+    /// procedural macros.
+    ///
+    /// Requires the `proc-macro` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path to the `phf` crate, a key or a value cannot be
+    /// tokenized.
+    #[cfg(feature = "proc-macro")]
+    pub fn to_token_stream(&self) -> proc_macro2::TokenStream {
+        let path = parse_tokens(self.path);
+        let key = self.state.key;
+        let disps = self.state.disps.iter().map(|&(d1, d2)| quote!((#d1, #d2)));
+        let suffix = self.index_type.unwrap_or("");
+        let idxs = self
+            .state
+            .map
+            .iter()
+            .map(|idx| parse_tokens(&format!("{}{}", idx, suffix)));
+        let entries = self
+            .keys
+            .iter()
+            .zip(self.values.iter())
+            .map(|(key, value)| {
+                let key = parse_tokens(&Delegate(key).to_string());
+                let value = parse_tokens(
+                    &value.to_prefixed_source(self.resolver.as_ref(), self.value_prefix),
+                );
+                quote!((#key, #value))
+            });
+
+        quote! {
+            #path::OrderedMap {
+                key: #key,
+                disps: &[#(#disps),*],
+                idxs: &[#(#idxs),*],
+                entries: &[#(#entries),*],
+            }
+        }
+    }
+}
+
+impl<'a, K: FmtConst + 'a> fmt::Display for DisplayOrderedMap<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.header, self.keys.len())?;
+        self.fmt_body(f)
+    }
+}
+
+impl<'a, K: FmtConst + 'a> DisplayOrderedMap<'a, K> {
+    fn fmt_body(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = self.style;
+        write!(f, "{}::OrderedMap {{", self.path)?;
+
+        style.begin_field(f, 0, "key")?;
+        write!(f, "{:?}", self.state.key)?;
+        style.end_field(f)?;
+
+        style.begin_field(f, 1, "disps")?;
+        f.write_str("&[")?;
+        for (i, &(d1, d2)) in self.state.disps.iter().enumerate() {
+            style.item(f, i, format_args!("({}, {})", d1, d2))?;
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        style.begin_field(f, 2, "idxs")?;
+        f.write_str("&[")?;
+        let suffix = self.index_type.unwrap_or("");
+        for (i, idx) in self.state.map.iter().enumerate() {
+            style.item(f, i, format_args!("{}{}", idx, suffix))?;
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        style.begin_field(f, 3, "entries")?;
+        f.write_str("&[")?;
+        for (i, (key, value)) in self.keys.iter().zip(self.values.iter()).enumerate() {
+            let value = value.to_prefixed_source(self.resolver.as_ref(), self.value_prefix);
+            style.item(f, i, format_args!("({}, {})", Delegate(key), value))?;
+        }
+        style.end_list(f)?;
+        style.end_field(f)?;
+
+        style.end_struct(f)
+    }
+}
+
+impl<'a, 'k> OrderedMap<'a, &'k [u8]> {
+    /// Constructs a new `phf::OrderedMap` builder for byte string keys.
+    ///
+    /// Byte string literals such as `b"loop"` are otherwise inferred as
+    /// references to fixed-size arrays, which do not match a
+    /// `phf::OrderedMap<&[u8], V>`. With this constructor they are coerced to
+    /// slices as they are added.
+    pub fn byte_keys() -> Self {
+        OrderedMap::new()
+    }
+}
+
+impl<'a, K, V> FromIterator<(K, V)> for OrderedMap<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst,
+    V: Into<Cow<'a, str>>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = OrderedMap::new();
+        for (key, value) in iter {
+            map.entry(key, value);
+        }
+        map
+    }
+}
+
+impl<'a, K, V> Extend<(K, V)> for OrderedMap<'a, K>
+where
+    K: Hash + PhfHash + Eq + FmtConst,
+    V: Into<Cow<'a, str>>,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.entry(key, value);
+        }
+    }
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> IntoIterator for OrderedMap<'a, K> {
+    type Item = (K, Cow<'a, str>);
+    type IntoIter = IntoIter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let resolved = match self.resolver() {
+            Some(resolver) => self
+                .values
+                .iter()
+                .map(|value| match value {
+                    Value::Resolved(_) => Some(value.to_source(Some(&resolver)).into_owned()),
+                    _ => None,
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        IntoIter {
+            iter: self.keys.into_iter().zip(self.values),
+            resolved: resolved.into_iter(),
+        }
+    }
+}
+
+/// A builder for the `phf::OrderedSet` type.
+pub struct OrderedSet<'a, T> {
+    map: OrderedMap<'a, T>,
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> OrderedSet<'a, T> {
+    /// Constructs a new `phf::OrderedSet` builder.
+    pub fn new() -> Self {
+        OrderedSet {
+            map: OrderedMap::new(),
+        }
+    }
+
+    /// Constructs a new `phf::OrderedSet` builder with space preallocated for
+    /// `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        OrderedSet {
+            map: OrderedMap::with_capacity(capacity),
+        }
+    }
+
+    /// Set the path to the `phf` crate from the global namespace
+    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.phf_path(path);
+        self
+    }
+
+    /// Set the path to the `phf` crate from its individual segments, such as
+    /// `&["my_crate", "phf"]` for `::my_crate::phf`.
+    ///
+    /// The path is absolute unless it starts with `crate`, `self` or `super`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `segments` is empty or any segment is not a valid identifier.
+    pub fn phf_path_segments(&mut self, segments: &[&str]) -> &mut Self {
+        self.map.phf_path_segments(segments);
+        self
+    }
+
+    /// Write the path to the `phf` crate without a leading `::`, such as
+    /// `phf::Map` rather than `::phf::Map`, so that it is resolved relative to
+    /// the scope the constructed source is included in.
+    ///
+    /// This is useful when `phf` is brought into scope by a `use` item rather
+    /// than being available at the root of the global namespace.
+    pub fn relative_path(&mut self) -> &mut Self {
+        self.map.relative_path();
+        self
+    }
+
+    /// Print the constructed source on a single line with minimal whitespace
+    /// rather than one entry per line.
+    pub fn compact(&mut self) -> &mut Self {
+        self.map.compact();
+        self
+    }
+
+    /// Set the width, in spaces, of one level of indentation in the
+    /// constructed source.
+    ///
+    /// Defaults to 4. Has no effect on [`compact`](Self::compact) output.
+    pub fn indent(&mut self, spaces: usize) -> &mut Self {
+        self.map.indent(spaces);
+        self
+    }
+
+    /// Prefix the constructed source with a `//` comment block containing
+    /// `text`, followed by a line noting the `phf_codegen` version and the
+    /// number of entries.
+    pub fn header(&mut self, text: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.header(text);
+        self
+    }
+
+    /// Mark the item printed by `as_const_item` with `#[rustfmt::skip]`, so
+    /// that running `cargo fmt` over an `include!`d file leaves it untouched.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output, as attributes cannot be applied to it.
+    pub fn rustfmt_skip(&mut self) -> &mut Self {
+        self.map.rustfmt_skip();
+        self
+    }
+
+    /// Precede the item printed by `as_const_item` with a `const` item named
+    /// `name` holding the number of entries, with the same visibility.
+    ///
+    /// This has no effect on the bare expression printed by the builder's
+    /// `Display` output.
+    pub fn with_len_const(&mut self, name: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.with_len_const(name);
+        self
+    }
+
+    /// Store the indices of the constructed `phf::OrderedSet` using the
+    /// smallest of `u8`, `u16`, `u32` and `usize` able to hold them, rather
+    /// than always using `usize`.
+    ///
+    /// The set's type must then name the index type, such as
+    /// `phf::OrderedSet<T, u16>`. It is available from
+    /// [`DisplayOrderedSet::index_type`] and is included by `as_const_item`.
+    pub fn narrow_indices(&mut self) -> &mut Self {
+        self.map.narrow_indices();
+        self
+    }
+
+    /// Adds an entry to the builder.
+    pub fn entry(&mut self, entry: T) -> &mut Self {
+        self.map.entry(entry, "()");
+        self
+    }
+
+    /// Adds every entry of `entries` to the builder.
+    ///
+    /// Prefer this over chaining many calls to `entry`, which can overflow the
+    /// compiler's stack.
+    pub fn entries_from<I: IntoIterator<Item = T>>(&mut self, entries: I) -> &mut Self {
+        for entry in entries {
+            self.entry(entry);
+        }
+        self
+    }
+
+    /// Appends all entries of `other` to the builder.
+    ///
+    /// Entries present in both builders are handled like any other duplicate
+    /// entry when building. Only the entries of `other` are taken; its
+    /// settings are discarded.
+    pub fn merge(&mut self, other: Self) -> &mut Self {
+        self.map.merge(other.map);
+        self
+    }
+
+    /// Returns the number of entries in the builder.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the builder contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the index of `entry` among the entries of the constructed
+    /// `phf::OrderedSet`, as returned by its `get_index` method.
+    ///
+    /// This is computed by a linear search over the entries.
+    pub fn index_of(&self, entry: &T) -> Option<usize> {
+        self.map.index_of(entry)
+    }
+
+    /// Returns an iterator over the entries in the builder, in insertion order.
+    pub fn entries(&self) -> impl Iterator<Item = &T> + '_ {
+        self.map.entries().map(|(k, _)| k)
+    }
+
+    /// Calculate the hash parameters and return a struct implementing
+    /// [`Display`](::std::fmt::Display) which will print the constructed
+    /// `phf::OrderedSet`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build(&self) -> DisplayOrderedSet<'_, T> {
+        DisplayOrderedSet {
+            inner: self.map.build(),
+        }
+    }
+
+    /// Like `build`, but returns the constructed source as a `String`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build_string(&self) -> String {
+        self.build().to_string()
+    }
+
+    /// Like `build`, but returns an error instead of panicking if there are
+    /// any duplicate entries.
+    pub fn try_build(&self) -> Result<DisplayOrderedSet<'_, T>, BuildError> {
+        Ok(DisplayOrderedSet {
+            inner: self.map.try_build()?,
+        })
+    }
+
+    /// Like `build`, but generates the hash with `hash_fn` rather than
+    /// [`phf_shared::hash`]; see `Map::build_with_hash_fn`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate entries, or if `hash_fn` maps too
+    /// many entries to the same hashes for a PHF to be found.
+    pub fn build_with_hash_fn<F>(&self, hash_fn: F) -> DisplayOrderedSet<'_, T>
+    where
+        F: Fn(&T, &HashKey) -> Hashes,
+    {
+        DisplayOrderedSet {
+            inner: self.map.build_with_hash_fn(hash_fn),
+        }
+    }
+}
+
+impl<'a, 'k> OrderedSet<'a, &'k [u8]> {
+    /// Constructs a new `phf::OrderedSet` builder for byte string entries.
+    ///
+    /// Byte string literals such as `b"loop"` are otherwise inferred as
+    /// references to fixed-size arrays, which do not match a
+    /// `phf::OrderedSet<&[u8]>`. With this constructor they are coerced to
+    /// slices as they are added.
+    pub fn byte_keys() -> Self {
+        OrderedSet::new()
+    }
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> FromIterator<T> for OrderedSet<'a, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = OrderedSet::new();
+        for entry in iter {
+            set.entry(entry);
+        }
+        set
+    }
+}
+
+impl<'a, T: Hash + PhfHash + Eq + FmtConst> Extend<T> for OrderedSet<'a, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for entry in iter {
+            self.entry(entry);
+        }
+    }
+}
+
+/// An adapter for printing a [`OrderedSet`](OrderedSet).
+pub struct DisplayOrderedSet<'a, T> {
+    inner: DisplayOrderedMap<'a, T>,
+}
+
+impl<'a, T: FmtConst + 'a> DisplayOrderedSet<'a, T> {
+    /// Writes the constructed `phf::OrderedSet` to `w`.
+    ///
+    /// The output is byte-for-byte identical to the [`Display`](fmt::Display)
+    /// implementation, but is streamed into the writer piece by piece rather
+    /// than being rendered into an intermediate `String` first.
+    pub fn write_to<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+
+    /// Returns the type used to store the indices of the constructed
+    /// `phf::OrderedSet`.
+    ///
+    /// This is `usize` unless [`OrderedSet::narrow_indices`] was called.
+    pub fn index_type(&self) -> &'static str {
+        self.inner.index_type()
+    }
+
+    /// Returns an adapter which will print the constructed `phf::OrderedSet`
+    /// as a complete `pub const` item named `name`, with the given element
+    /// type.
+    pub fn as_const_item<'b>(&'b self, name: &'b str, ty: &str) -> DisplayItem<'b, Self> {
+        let ty = match self.inner.index_type {
+            Some(index_ty) => format!("{}::OrderedSet<{}, {}>", self.inner.path, ty, index_ty),
+            None => format!("{}::OrderedSet<{}>", self.inner.path, ty),
+        };
+        DisplayItem {
+            kind: "const",
+            vis: "pub",
+            name,
+            ty,
+            rustfmt_skip: self.inner.style.rustfmt_skip,
+            len_const: self
+                .inner
+                .len_const
+                .map(|name| (name, self.inner.keys.len())),
+            statics: None,
+            cfg: None,
+            value: self,
+        }
+    }
+
+    /// Like `as_const_item`, but prints a `pub static` item instead.
+    pub fn as_static_item<'b>(&'b self, name: &'b str, ty: &str) -> DisplayItem<'b, Self> {
+        DisplayItem {
+            kind: "static",
+            ..self.as_const_item(name, ty)
+        }
+    }
+
+    /// Returns the constructed `phf::OrderedSet` as tokens, for use in
+    /// procedural macros.
+    ///
+    /// Requires the `proc-macro` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path to the `phf` crate or an entry cannot be tokenized.
+    #[cfg(feature = "proc-macro")]
+    pub fn to_token_stream(&self) -> proc_macro2::TokenStream {
+        let path = parse_tokens(self.inner.path);
+        let map = self.inner.to_token_stream();
+        quote!(#path::OrderedSet { map: #map })
+    }
+}
+
+impl<'a, T: FmtConst + 'a> fmt::Display for DisplayOrderedSet<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_header(f, self.inner.header, self.inner.keys.len())?;
+        write!(f, "{}::OrderedSet {{ map: ", self.inner.path)?;
+        self.inner.fmt_body(f)?;
+        f.write_str(" }")
+    }
+}
+
+/// A builder for a function which looks up the index of a key with an
+/// inlined perfect hash.
+///
+/// The constructed function takes a key and returns the insertion index of
+/// its entry in the builder, or `None` if it is not one of the keys. The hash
+/// parameters and keys are written into the function as constants, so no
+/// `phf::Map` is involved in the lookup.
+///
+/// ```rust
+/// let mut builder = phf_codegen::Dispatcher::new();
+/// builder.entry("fn").entry("let").entry("loop");
+/// let source = builder.build().as_fn("keyword", "str", "&str").to_string();
+/// assert!(source.starts_with("pub fn keyword(key: &str) -> ::core::option::Option<usize> {"));
+/// ```
+pub struct Dispatcher<'a, K> {
+    map: Map<'a, K>,
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> Dispatcher<'a, K> {
+    /// Constructs a new dispatcher builder.
+    pub fn new() -> Self {
+        Dispatcher { map: Map::new() }
+    }
+
+    /// Set the path to the `phf` crate from the global namespace
+    pub fn phf_path(&mut self, path: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.map.phf_path(path);
+        self
+    }
+
+    /// Adds a key to the builder, with the next insertion index.
+    pub fn entry(&mut self, key: K) -> &mut Self {
+        self.map.entry(key, "()");
+        self
+    }
+
+    /// Adds every key of `keys` to the builder.
+    pub fn entries_from<I>(&mut self, keys: I) -> &mut Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        for key in keys {
+            self.entry(key);
+        }
+        self
+    }
+
+    /// Returns the number of keys in the builder.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the builder contains no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Calculate the hash parameters and return a struct which can print the
+    /// constructed function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are any duplicate keys.
+    pub fn build(&self) -> DisplayDispatcher<'_, K> {
+        self.try_build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Like `build`, but returns an error instead of panicking if there are
+    /// any duplicate keys.
+    pub fn try_build(&self) -> Result<DisplayDispatcher<'_, K>, BuildError> {
+        self.map
+            .try_build()
+            .map(|inner| DisplayDispatcher { inner })
+    }
+}
+
+impl<'a, K: Hash + PhfHash + Eq + FmtConst> Default for Dispatcher<'a, K> {
+    fn default() -> Self {
+        Dispatcher::new()
+    }
+}
+
+/// The hash parameters of a [`Dispatcher`](Dispatcher).
+pub struct DisplayDispatcher<'a, K> {
+    inner: DisplayMap<'a, K>,
+}
+
+impl<'a, K: FmtConst + 'a> DisplayDispatcher<'a, K> {
+    /// Returns an adapter which will print the constructed function as a
+    /// `pub fn` named `name`.
+    ///
+    /// The function takes its key as `&arg_ty`, and the keys are stored as
+    /// `key_ty`, which must implement `PhfBorrow<arg_ty>`, as for the key
+    /// type of a `phf::Map<key_ty, V>` looked up with an `&arg_ty`.
+    pub fn as_fn<'b>(
+        &'b self,
+        name: &'b str,
+        arg_ty: &'b str,
+        key_ty: &'b str,
+    ) -> DisplayDispatchFn<'b, K> {
+        DisplayDispatchFn {
+            vis: "pub",
+            name,
+            arg_ty,
+            key_ty,
+            dispatcher: self,
+        }
+    }
+}
+
+/// An adapter for printing a [`Dispatcher`](Dispatcher) as a function.
+pub struct DisplayDispatchFn<'a, K> {
+    vis: &'a str,
+    name: &'a str,
+    arg_ty: &'a str,
+    key_ty: &'a str,
+    dispatcher: &'a DisplayDispatcher<'a, K>,
+}
+
+impl<'a, K> DisplayDispatchFn<'a, K> {
+    /// Set the visibility of the function, such as `pub(crate)`.
+    ///
+    /// Defaults to `pub`. An empty string makes the function private.
+    pub fn vis(mut self, vis: &'a str) -> Self {
+        self.vis = vis;
+        self
+    }
+}
+
+impl<'a, K: FmtConst + 'a> fmt::Display for DisplayDispatchFn<'a, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let map = &self.dispatcher.inner;
+        let path = map.path;
+        if !self.vis.is_empty() {
+            write!(f, "{} ", self.vis)?;
+        }
+        writeln!(
+            f,
+            "fn {}(key: &{}) -> ::core::option::Option<usize> {{",
+            self.name, self.arg_ty
+        )?;
+        if map.keys.is_empty() {
+            f.write_str("    let _ = key;\n    ::core::option::Option::None\n}")?;
+            return Ok(());
+        }
+
+        writeln!(f, "    const KEY: u64 = {:?};", map.state.key)?;
+        f.write_str("    const DISPS: &[(u32, u32)] = &[\n")?;
+        for &(d1, d2) in &map.state.disps {
+            writeln!(f, "        ({}, {}),", d1, d2)?;
+        }
+        f.write_str("    ];\n")?;
+        writeln!(f, "    const ENTRIES: &[({}, usize)] = &[", self.key_ty)?;
+        for &idx in &map.state.map {
+            writeln!(f, "        ({}, {}),", Delegate(map.keys[idx]), idx)?;
+        }
+        f.write_str("    ];\n")?;
+        writeln!(f, "    let hashes = {}::__private::hash(key, &KEY);", path)?;
+        writeln!(
+            f,
+            "    let slot = {}::__private::get_index(&hashes, DISPS, ENTRIES.len()) as usize;",
+            path
+        )?;
+        f.write_str("    let (entry, index) = &ENTRIES[slot];\n")?;
+        writeln!(
+            f,
+            "    if {}::__private::PhfBorrow::<{}>::borrow(entry) == key {{",
+            path, self.arg_ty
+        )?;
+        f.write_str("        ::core::option::Option::Some(*index)\n")?;
+        f.write_str("    } else {\n        ::core::option::Option::None\n    }\n}")
+    }
+}
+
+/// An adapter for printing a constructed PHF type as a complete item, such as
+/// `pub const NAME: phf::Map<K, V> = ...;`.
+pub struct DisplayItem<'a, D> {
+    kind: &'static str,
+    vis: &'a str,
+    name: &'a str,
+    ty: String,
+    rustfmt_skip: bool,
+    len_const: Option<(&'a str, usize)>,
+    statics: Option<Box<dyn ItemStatics + 'a>>,
+    cfg: Option<String>,
+    value: &'a D,
+}
+
+/// Backing arrays printed as `static` items ahead of a [`DisplayItem`], whose
+/// value then refers to them.
+trait ItemStatics {
+    fn fmt_arrays(&self, f: &mut fmt::Formatter<'_>, cfg: Option<&str>) -> fmt::Result;
+
+    fn fmt_value(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<'a, D> DisplayItem<'a, D> {
+    /// Set the visibility of the item, such as `pub(crate)`.
+    ///
+    /// Defaults to `pub`. An empty string makes the item private.
+    pub fn vis(mut self, vis: &'a str) -> Self {
+        self.vis = vis;
+        self
+    }
+}
+
+impl<'a, D: fmt::Display> fmt::Display for DisplayItem<'a, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cfg = self.cfg.as_deref();
+        if let Some((name, len)) = self.len_const {
+            write_cfg(f, cfg)?;
+            if !self.vis.is_empty() {
+                write!(f, "{} ", self.vis)?;
+            }
+            writeln!(f, "const {}: usize = {};", name, len)?;
+        }
+        if let Some(statics) = &self.statics {
+            statics.fmt_arrays(f, cfg)?;
+        }
+        write_cfg(f, cfg)?;
+        if self.rustfmt_skip {
+            f.write_str("#[rustfmt::skip]\n")?;
+        }
+        if !self.vis.is_empty() {
+            write!(f, "{} ", self.vis)?;
+        }
+        write!(f, "{} {}: {} = ", self.kind, self.name, self.ty)?;
+        match &self.statics {
+            Some(statics) => statics.fmt_value(f)?,
+            None => write!(f, "{}", self.value)?,
+        }
+        f.write_str(";")
+    }
+}
+
+fn write_cfg(f: &mut fmt::Formatter<'_>, cfg: Option<&str>) -> fmt::Result {
+    match cfg {
+        Some(cfg) => writeln!(f, "#[cfg({})]", cfg),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_build_duplicate_key() {
+        let mut builder = Map::new();
+        builder.entry("a", "1").entry("b", "2").entry("a", "3");
+        match builder.try_build() {
+            Err(BuildError::DuplicateKey {
+                index,
+                first_index,
+                key,
+            }) => {
+                assert_eq!(2, index);
+                assert_eq!(0, first_index);
+                assert_eq!("\"a\"", key);
+            }
+            _ => panic!("expected a duplicate key error"),
+        }
+        assert_eq!(
+            "duplicate key `\"a\"` at index 2, first added at index 0",
+            builder.try_build().err().unwrap().to_string()
+        );
+
+        let mut builder = OrderedSet::new();
+        builder.entry(1u32).entry(1u32);
+        assert!(builder.try_build().is_err());
+    }
+
+    #[test]
+    fn try_build_unrepresentable_key() {
+        #[derive(Hash, PartialEq, Eq)]
+        struct Key(u32);
+
+        impl PhfHash for Key {
+            fn phf_hash<H: Hasher>(&self, state: &mut H) {
+                self.0.phf_hash(state)
+            }
+        }
+
+        impl FmtConst for Key {
+            fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.0 == 0 {
+                    return Err(fmt::Error);
+                }
+                write!(f, "Key({})", self.0)
+            }
+        }
+
+        let mut builder = Map::new();
+        builder.entry(Key(1), "1").entry(Key(0), "2");
+        assert_eq!(
+            Err(BuildError::UnrepresentableKey { index: 1 }),
+            builder.try_build().map(|_| ())
+        );
+        assert_eq!(
+            "key at index 1 cannot be represented as a const expression",
+            builder.try_build().err().unwrap().to_string()
+        );
+
+        let mut builder = OrderedSet::new();
+        builder.entry(Key(0));
+        assert!(builder.try_build().is_err());
+    }
+
+    #[test]
+    fn try_build_unrepresentable_key_check_fmt_const() {
+        #[derive(Hash, PartialEq, Eq)]
+        struct Key(u32);
+
+        impl PhfHash for Key {
+            fn phf_hash<H: Hasher>(&self, state: &mut H) {
+                self.0.phf_hash(state)
+            }
+        }
+
+        impl FmtConst for Key {
+            fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "Key({})", self.0)
+            }
+
+            fn check_fmt_const(&self) -> Result<(), phf_shared::FmtConstError> {
+                if self.0 == 0 {
+                    return Err(phf_shared::FmtConstError);
+                }
+                Ok(())
+            }
+        }
+
+        let mut builder = Map::new();
+        builder.entry(Key(1), "1").entry(Key(0), "2");
+        assert_eq!(
+            Err(BuildError::UnrepresentableKey { index: 1 }),
+            builder.try_build().map(|_| ())
+        );
+
+        let mut builder = Map::new();
+        builder
+            .entry((1u32, Key(1)), "1")
+            .entry((2u32, Key(0)), "2");
+        assert_eq!(
+            Err(BuildError::UnrepresentableKey { index: 1 }),
+            builder.try_build().map(|_| ())
+        );
+
+        let mut builder = Set::new();
+        builder.entry(Key(0));
+        assert!(builder.try_build().is_err());
+    }
+
+    #[test]
+    fn try_build_nan_key() {
+        struct Key(f64);
+
+        impl Hash for Key {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state)
+            }
+        }
+
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for Key {}
+
+        impl PhfHash for Key {
+            fn phf_hash<H: Hasher>(&self, state: &mut H) {
+                self.0.phf_hash(state)
+            }
+        }
+
+        impl FmtConst for Key {
+            fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt_const(f)
+            }
+
+            fn check_fmt_const(&self) -> Result<(), phf_shared::FmtConstError> {
+                self.0.check_fmt_const()
+            }
+        }
+
+        let mut builder = Map::new();
+        builder.entry(Key(1.5), "1").entry(Key(f64::NAN), "2");
+        assert_eq!(
+            Err(BuildError::UnrepresentableKey { index: 1 }),
+            builder.try_build().map(|_| ())
+        );
+    }
+
+    #[test]
+    fn builder_entries() {
+        let mut builder = Map::new();
+        assert!(builder.is_empty());
+        builder.entry("a", "1").entry("b", "2");
+        assert_eq!(2, builder.len());
+        assert_eq!(
+            vec![(&"a", "1".into()), (&"b", "2".into())],
+            builder.entries().collect::<Vec<_>>()
+        );
+
+        let mut builder = OrderedSet::new();
+        builder.entry(3u32).entry(1u32);
+        assert_eq!(2, builder.len());
+        assert_eq!(vec![&3, &1], builder.entries().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_iter() {
+        let builder = vec![("a", "1"), ("b", "2")].into_iter().collect::<Map<_>>();
+        assert_eq!(
+            vec![("a", Cow::Borrowed("1")), ("b", Cow::Borrowed("2"))],
+            builder.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn entry_with() {
+        let mut builder = Map::new();
+        builder
+            .entry(1u32, "\"a\"")
+            .entry_with(2u32, || format!("{:?}", "b"));
+        assert_eq!(
+            vec![(&1, "\"a\"".into()), (&2, "\"b\"".into())],
+            builder.entries().collect::<Vec<_>>()
+        );
+        assert!(builder.build().to_string().contains("(2, \"b\")"));
+
+        let mut builder = OrderedMap::new();
+        builder.entry_with(1u32, || "Some(1)".to_string());
+        assert!(builder.build().to_string().contains("(1, Some(1))"));
+        assert_eq!(
+            vec![(1, Cow::Owned::<str>("Some(1)".to_string()))],
+            builder.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn entries_from() {
+        let mut builder = Map::new();
+        builder
+            .entry("a", "1")
+            .entries_from(vec![("b", "2"), ("c", "3")]);
+        assert_eq!(3, builder.len());
+
+        let mut builder = OrderedMap::new();
+        builder.entries_from((0..1000u32).map(|i| (i, i.to_string())));
+        assert_eq!(1000, builder.len());
+
+        let mut builder = Set::new();
+        builder.entries_from(1..4u32).entry(10);
+        assert_eq!(vec![&1, &2, &3, &10], builder.entries().collect::<Vec<_>>());
+
+        let mut builder = OrderedSet::new();
+        builder.entries_from(vec![3u32, 1]);
+        assert_eq!(vec![&3, &1], builder.entries().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn raw_key() {
+        let mut builder = Map::new();
+        builder
+            .entry(RawKey::new(1u32, "ONE"), "1")
+            .entry(RawKey::new(1u32, "UNO"), "1");
+        match builder.try_build() {
+            Err(BuildError::DuplicateKey { index, key, .. }) => {
+                assert_eq!(1, index);
+                assert_eq!("UNO", key);
+            }
+            _ => panic!("expected a duplicate key error"),
+        }
+
+        let mut builder = OrderedSet::new();
+        builder.entry(RawKey::new('a', "A"));
+        assert!(builder
+            .build()
+            .to_string()
+            .contains("entries: &[\n        (A, ()),"));
+    }
+
+    #[test]
+    fn build_string() {
+        let mut builder = Map::new();
+        builder.entry(1u32, "2");
+        assert_eq!(builder.build().to_string(), builder.build_string());
+
+        let mut builder = Set::new();
+        builder.entry(1u32);
+        assert_eq!(builder.build().to_string(), builder.build_string());
+
+        let mut builder = OrderedMap::new();
+        builder.entry(1u32, "2");
+        assert_eq!(builder.build().to_string(), builder.build_string());
+
+        let mut builder = OrderedSet::new();
+        builder.entry(1u32);
+        assert_eq!(builder.build().to_string(), builder.build_string());
+    }
+
+    #[test]
+    fn insertion_order_independent() {
+        let keys = (0..100u32).collect::<Vec<_>>();
+        let forward = keys.iter().map(|&k| (k, k.to_string())).collect::<Map<_>>();
+        let reverse = keys
+            .iter()
+            .rev()
+            .map(|&k| (k, k.to_string()))
+            .collect::<Map<_>>();
+        assert_eq!(forward.build_string(), reverse.build_string());
+    }
+
+    #[test]
+    fn entry_resolved() {
+        let mut builder = Map::new();
+        for i in 0..10u32 {
+            builder.entry_resolved(i, move |resolver| {
+                format!("{}", resolver.index_of(&((i + 1) % 10)).unwrap())
+            });
+        }
+        let built = builder.build();
+        let slots = built.state.map.clone();
+        let source = built.to_string();
+        for (slot, &idx) in slots.iter().enumerate() {
+            let next = slots.iter().position(|&j| j == (idx + 1) % 10).unwrap();
+            assert!(
+                source.contains(&format!("({}, {}),", idx, next)),
+                "{}",
+                slot
+            );
+        }
+
+        let entries = builder
+            .entries()
+            .map(|(&key, value)| (key, value.into_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(10, entries.len());
+        assert_eq!(
+            entries,
+            builder
+                .into_iter()
+                .map(|(key, value)| (key, value.into_owned()))
+                .collect::<Vec<_>>()
+        );
+
+        let mut builder = OrderedMap::new();
+        builder
+            .entry_resolved("a", |resolver| format!("{:?}", resolver.index_of(&"b")))
+            .entry("b", "None")
+            .entry_resolved("c", |resolver| format!("{:?}", resolver.index_of(&"d")));
+        let source = builder.build().to_string();
+        assert!(source.contains("(\"a\", Some(1)),"));
+        assert!(source.contains("(\"c\", None),"));
+    }
+
+    #[test]
+    fn build_with_hash_fn() {
+        // hashing keys by their lowercase form matches looking them up with
+        // `phf_shared::hash` once they are lowercased
+        let mut builder = Map::new();
+        builder.entry("Foo", "1").entry("BAR", "2");
+        let built = builder
+            .build_with_hash_fn(|key, hash_key| phf_shared::hash(&*key.to_lowercase(), hash_key));
+        let mut expected = Map::new();
+        expected.entry("foo", "1").entry("bar", "2");
+        let expected = expected.build();
+        assert_eq!(expected.state.key, built.state.key);
+        assert_eq!(expected.state.disps, built.state.disps);
+        assert_eq!(expected.state.map, built.state.map);
+
+        let mut builder = OrderedSet::new();
+        builder.entry(1u32).entry(2u32);
+        let default = builder.build().to_string();
+        let custom = builder.build_with_hash_fn(phf_shared::hash).to_string();
+        assert_eq!(default, custom);
+    }
+
+    #[test]
+    fn try_build_with_hash_fn_unsolvable() {
+        // every key hashes the same under every hash key
+        fn constant(_: &&str, _: &HashKey) -> Hashes {
+            phf_shared::hash(&0u32, &0)
+        }
+
+        let mut builder = Map::new();
+        builder.entry("a", "1").entry("b", "2");
+        assert!(matches!(
+            builder.try_build_with_hash_fn(constant),
+            Err(BuildError::Unsolvable(_))
+        ));
+
+        let mut builder = OrderedMap::new();
+        builder.entry("a", "1").entry("b", "2");
+        assert!(matches!(
+            builder.try_build_with_hash_fn(constant),
+            Err(BuildError::Unsolvable(_))
+        ));
+    }
+
+    #[test]
+    fn index_of() {
+        let mut builder = OrderedMap::new();
+        builder.entry("a", "1").entry("b", "2").entry("c", "3");
+        assert_eq!(Some(1), builder.index_of(&"b"));
+        assert_eq!(None, builder.index_of(&"d"));
+
+        builder.dedup_last_wins().entry("a", "4").entry("d", "5");
+        assert_eq!(Some(0), builder.index_of(&"a"));
+        assert_eq!(Some(3), builder.index_of(&"d"));
+
+        let mut builder = OrderedSet::new();
+        builder.entry(3u32).entry(1u32);
+        assert_eq!(Some(1), builder.index_of(&1));
+    }
+
+    #[test]
+    fn build_binary_search() {
+        let mut builder = Map::new();
+        builder.entry(3u32, "c").entry(1u32, "a").entry(2u32, "b");
+        assert_eq!(
+            "::phf::SortedMap { entries: &[(1, a), (2, b), (3, c)] }",
+            builder.compact().build_binary_search().to_string()
+        );
+
+        builder.entry(1u32, "d");
+        assert!(builder.try_build_binary_search().is_err());
+    }
+
+    #[test]
+    fn from_std_maps() {
+        let mut btree_map = BTreeMap::new();
+        btree_map.insert(1u32, "\"a\"".to_string());
+        btree_map.insert(2u32, "\"b\"".to_string());
+        let hash_map: HashMap<_, _> = btree_map.clone().into_iter().collect();
+
+        let expected = Map::from_iter(vec![(1u32, "\"a\""), (2u32, "\"b\"")])
+            .build()
+            .to_string();
+        assert_eq!(expected, Map::from(&btree_map).build().to_string());
+        assert_eq!(expected, Map::from(&hash_map).build().to_string());
+    }
+
+    #[test]
+    fn from_iter() {
+        let builder: OrderedMap<_> = vec![(2u32, "b"), (1u32, "a")].into_iter().collect();
+        assert_eq!(Some(0), builder.index_of(&2));
+
+        let builder: Set<_> = vec![1u32, 2].into_iter().collect();
+        assert_eq!(2, builder.len());
+
+        let builder: OrderedSet<_> = vec![2u32, 1].into_iter().collect();
+        assert_eq!(vec![&2, &1], builder.entries().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dispatcher() {
+        let builder = Dispatcher::<u32>::new();
+        assert_eq!(
+            "fn lookup(key: &u32) -> ::core::option::Option<usize> {\n    \
+             let _ = key;\n    ::core::option::Option::None\n}",
+            builder
+                .build()
+                .as_fn("lookup", "u32", "u32")
+                .vis("")
+                .to_string()
+        );
+
+        let mut builder = Dispatcher::new();
+        builder.entries_from(["a", "b", "a"]);
+        assert!(builder.try_build().is_err());
+    }
+
+    #[test]
+    fn entry_nested() {
+        let mut inner = Map::new();
+        inner.compact().entry(1u32, "2");
+        let inner = inner.build();
+
+        let mut builder = Map::new();
+        builder.entry_nested("a", &inner, "u32", "u32");
+        let source = builder.build().to_string();
+        assert!(source.contains(&format!(
+            "(\"a\", {{ const NESTED: ::phf::Map<u32, u32> = {}; NESTED }}),",
+            inner
+        )));
+
+        builder.value_prefix("crate::V").entry("b", "B");
+        let source = builder.build().to_string();
+        assert!(source.contains("(\"a\", { const NESTED: "));
+        assert!(source.contains("(\"b\", crate::V::B),"));
+    }
+
+    #[test]
+    fn static_attrs() {
+        let mut builder = Map::new();
+        builder
+            .static_attrs(&["#[link_section = \".rodata.phf\"]", "#[used]"])
+            .entry(1u32, "2");
+        let built = builder.build();
+        assert!(!built.to_string().contains("MAP_DISPS"));
+        assert!(!built
+            .as_const_item("MAP", "u32", "u32")
+            .to_string()
+            .contains("MAP_DISPS"));
+
+        let source = built.as_static_item("MAP", "u32", "u32").to_string();
+        assert!(source.starts_with(
+            "#[link_section = \".rodata.phf\"]\n#[used]\nstatic MAP_DISPS: [(u32, u32); 1] = [\n    (0, 0),\n];\n"
+        ));
+        assert!(source.contains(
+            "#[link_section = \".rodata.phf\"]\n#[used]\nstatic MAP_ENTRIES: [(u32, u32); 1] = [\n    (1, 2),\n];\n"
+        ));
+        assert!(source.contains("pub static MAP: ::phf::Map<u32, u32> = ::phf::Map {\n"));
+        assert!(source.ends_with("    disps: &MAP_DISPS,\n    entries: &MAP_ENTRIES,\n};"));
+    }
+
+    #[test]
+    fn extend() {
+        let mut builder = Map::new();
+        builder.extend(vec![("a", "1")]);
+        builder.extend(vec![("b", "2"), ("c", "3")]);
+        assert_eq!(3, builder.len());
+
+        let mut builder = Set::new();
+        builder.extend(1..4u32);
+        builder.extend(Some(10u32));
+        assert_eq!(vec![&1, &2, &3, &10], builder.entries().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn header() {
+        let mut builder = Set::new();
+        builder
+            .header("Generated by build.rs.\n\nDo not edit.")
+            .entry(1u32)
+            .entry(2u32);
+        let source = builder.build().to_string();
+        let expected = format!(
+            "// Generated by build.rs.\n//\n// Do not edit.\n// phf_codegen {}, 2 entries\n::phf::Set {{ map: ::phf::Map {{",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(source.starts_with(&expected));
+    }
+
+    #[test]
+    #[cfg(feature = "validate")]
+    fn validate_values() {
+        let mut builder = Map::new();
+        builder
+            .validate_values()
+            .entry("a", "Keyword::Loop")
+            .entry("b", "Some((1, 2)");
+        match builder.try_build() {
+            Err(BuildError::InvalidValue { index, value, .. }) => {
+                assert_eq!(1, index);
+                assert_eq!("Some((1, 2)", value);
+            }
+            _ => panic!("expected an invalid value error"),
+        }
+
+        let mut builder = OrderedMap::new();
+        builder.validate_values().entry("a", "[1, 2, 3]");
+        assert!(builder.try_build().is_ok());
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = Map::new();
+        a.entry("a", "1");
+        let mut b = Map::new();
+        b.entry("b", "2").entry("c", "3");
+        a.merge(b);
+        assert_eq!(
+            vec![(&"a", "1".into()), (&"b", "2".into()), (&"c", "3".into())],
+            a.entries().collect::<Vec<_>>()
+        );
+
+        let mut a = OrderedSet::new();
+        a.entry(1u32);
+        let mut b = OrderedSet::new();
+        b.entry(1u32);
+        a.merge(b);
+        assert!(a.try_build().is_err());
+    }
+
+    #[test]
+    fn cstr_keys() {
+        use std::ffi::CStr;
+
+        let keys = [
+            CStr::from_bytes_with_nul(b"open\0").unwrap(),
+            CStr::from_bytes_with_nul(b"say \"hi\"\n\xff\0").unwrap(),
+        ];
+        let mut builder = Map::new();
+        builder.entry(keys[0], "1").entry(keys[1], "2");
+        let built = builder.build();
+        assert!(phf_generator::verify(&keys, built.hash_state()));
+
+        let source = built.to_string();
+        assert!(source.contains("(c\"open\", 1),"));
+        assert!(source.contains("(c\"say \\\"hi\\\"\\n\\xff\", 2),"));
+
+        let key = HashKey::default();
+        assert_eq!(
+            phf_shared::hash(keys[0], &key).g,
+            phf_shared::hash(&b"open"[..], &key).g
+        );
+    }
+
+    #[test]
+    fn build_owned_arrays() {
+        let mut builder = Map::new();
+        builder.entry(1u32, "2").entry(3u32, "4");
+        let source = builder
+            .build_owned_arrays()
+            .as_static_item("MAP", "u32", "u32")
+            .to_string();
+        assert!(source
+            .starts_with("pub static MAP: ::phf::ArrayMap<u32, u32, 1, 2> = ::phf::ArrayMap {\n"));
+        assert!(source.contains("\n    disps: [\n"));
+        assert!(source.contains("\n    entries: [\n"));
+        assert!(!source.contains('&'));
+    }
+
+    #[test]
+    fn inverse() {
+        let mut builder = Map::new();
+        builder
+            .dedup_last_wins()
+            .entry("a", "1")
+            .entry("b", "2")
+            .entry("a", "3");
+        let inverse = builder.inverse(|value| value.parse::<u32>().unwrap());
+        assert_eq!(
+            vec![(&3, "\"a\"".into()), (&2, "\"b\"".into())],
+            inverse.entries().collect::<Vec<_>>()
+        );
+
+        let mut builder = Map::new();
+        builder
+            .entry("a", "1")
+            .entry_cfg("b", "2", "unix")
+            .entry_cfg("b", "3", "windows");
+        let inverse = builder.inverse(|value| value.parse::<u32>().unwrap());
+        assert!(inverse.try_build().is_err());
+        let source = inverse
+            .build_cfg()
+            .as_const_item("INVERSE", "u32", "&str")
+            .to_string();
+        assert!(source.contains("#[cfg(all(unix, windows))]\npub const INVERSE: "));
+    }
+
+    #[test]
+    fn build_cfg() {
+        let mut builder = Map::new();
+        builder
+            .entry("a", "1")
+            .entry_cfg("b", "2", "unix")
+            .entry_cfg("c", "3", "unix")
+            .entry_cfg("b", "4", "windows");
+        assert_eq!(
+            Err(BuildError::CfgEntry { index: 1 }),
+            builder.try_build().map(|_| ())
+        );
+
+        let source = builder
+            .build_cfg()
+            .as_static_item("MAP", "&str", "u32")
+            .to_string();
+        for cfg in [
+            "all(not(unix), not(windows))",
+            "all(unix, not(windows))",
+            "all(not(unix), windows)",
+        ] {
+            assert!(source.contains(&format!("#[cfg({})]\npub static MAP: ", cfg)));
+        }
+        assert!(source.ends_with(
+            "#[cfg(all(unix, windows))]\ncompile_error!(\"duplicate key `\\\"b\\\"` at index 3, first added at index 1\");"
+        ));
+    }
+
+    #[test]
+    fn build_cfg_too_many_predicates() {
+        let mut builder = Map::new();
+        for i in 0..MAX_CFG_PREDICATES {
+            builder.entry_cfg(i as u32, "0", format!("feature = \"{}\"", i));
+        }
+        builder.entry_cfg(100, "0", "feature = \"0\"");
+        assert!(builder.try_build_cfg().is_ok());
+
+        builder.entry_cfg(101, "0", "unix");
+        assert_eq!(
+            Err(BuildError::TooManyCfgPredicates { count: 13 }),
+            builder.try_build_cfg().map(|_| ())
+        );
+        assert_eq!(
+            "13 distinct cfg predicates were given, but at most 12 are supported",
+            builder.try_build_cfg().err().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn build_cfg_duplicate_key() {
+        let mut builder = Map::new();
+        builder
+            .entry("a", "1")
+            .entry_cfg("b", "2", "unix")
+            .entry("a", "3");
+        match builder.try_build_cfg() {
+            Err(BuildError::DuplicateKey {
+                index, first_index, ..
+            }) => {
+                assert_eq!(2, index);
+                assert_eq!(0, first_index);
+            }
+            _ => panic!("expected a duplicate key error"),
+        }
+    }
+
+    #[test]
+    fn build_assume_unique() {
+        let mut builder = Map::new();
+        builder.entry("a", "1").entry("b", "2");
+        assert_eq!(
+            builder.build().to_string(),
+            builder.build_assume_unique().to_string()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key `\"a\"` at index 2, first added at index 0")]
+    fn build_assume_unique_duplicate_key() {
+        let mut builder = Map::new();
+        builder.entry("a", "1").entry("b", "2").entry("a", "3");
+        builder.build_assume_unique();
+    }
+
+    #[test]
+    fn annotate_entries() {
+        let mut builder = Map::new();
+        builder
+            .annotate_entries()
+            .case_insensitive_str()
+            .entry("Loop", "1");
+        let source = builder.build().to_string();
+        assert!(source.contains("\n        (\"loop\", 1), // key=\"Loop\"\n"));
+
+        builder.compact();
+        assert!(!builder.build().to_string().contains("//"));
+    }
+
+    #[test]
+    fn hash_state() {
+        let keys = ["a", "b", "c"];
+        let mut builder = Map::new();
+        for key in keys {
+            builder.entry(key, "0");
+        }
+        let built = builder.build();
+        assert_eq!(3, built.hash_state().map.len());
+        assert!(phf_generator::verify(&keys, built.hash_state()));
+        assert!(built
+            .to_string()
+            .contains(&format!("key: {:?},", built.hash_state().key)));
+    }
+
+    #[test]
+    fn hash_state_case_insensitive() {
+        let mut builder = Map::new();
+        builder
+            .case_insensitive_str()
+            .entry("Content-Type", "0")
+            .entry("Accept", "1")
+            .entry("X-Request-ID", "2");
+        let built = builder.build();
+        assert!(phf_generator::verify(
+            &["content-type", "accept", "x-request-id"],
+            built.hash_state()
+        ));
+        assert!(!phf_generator::verify(
+            &["Content-Type", "Accept", "X-Request-ID"],
+            built.hash_state()
+        ));
+    }
+
+    #[test]
+    fn retain() {
+        let mut builder = Map::new();
+        builder
+            .entry("loop", "1")
+            .entry("do", "0")
+            .entry("while", "2")
+            .entry("become", "0");
+        builder.retain(|key, value| *key != "loop" && value != "0");
+        assert_eq!(
+            vec![(&"while", "2".into())],
+            builder.entries().collect::<Vec<_>>()
+        );
+        assert!(builder.build().to_string().contains("(\"while\", 2),"));
+    }
+
+    #[test]
+    fn relative_path() {
+        let mut builder = Map::new();
+        builder.relative_path().entry(1u32, "2");
+        let source = builder
+            .build()
+            .as_const_item("MAP", "u32", "u32")
+            .to_string();
+        assert!(source.starts_with("pub const MAP: phf::Map<u32, u32> = phf::Map {"));
+
+        let mut builder = OrderedSet::new();
+        builder
+            .relative_path()
+            .phf_path_segments(&["my_crate", "phf"])
+            .entry(1u32);
+        assert!(builder
+            .build()
+            .to_string()
+            .starts_with("my_crate::phf::OrderedSet { map: my_crate::phf::OrderedMap {"));
+    }
+
+    #[test]
+    fn phf_path_segments() {
+        assert_eq!("::phf", join_path_segments(&["phf"]));
+        assert_eq!("::my_crate::phf", join_path_segments(&["my_crate", "phf"]));
+        assert_eq!(
+            "crate::deps::phf",
+            join_path_segments(&["crate", "deps", "phf"])
+        );
+        assert_eq!(
+            "super::super::phf",
+            join_path_segments(&["super", "super", "phf"])
+        );
+        assert_eq!("::r#type::phf", join_path_segments(&["r#type", "phf"]));
+
+        let mut builder = Set::new();
+        builder.phf_path_segments(&["my_crate", "phf"]).entry(1u32);
+        assert!(builder
+            .build()
+            .to_string()
+            .starts_with("::my_crate::phf::Set {"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid `phf` path segment `my crate`")]
+    fn phf_path_segments_invalid() {
+        Map::<u32>::new().phf_path_segments(&["my crate", "phf"]);
+    }
+
+    #[test]
+    fn rustfmt_skip() {
+        let mut builder = Map::new();
+        builder.rustfmt_skip().entry(1u32, "2");
+        let built = builder.build();
+        assert!(!built.to_string().contains("rustfmt"));
+
+        let source = built.as_const_item("MAP", "u32", "u32").to_string();
+        assert!(source.starts_with("#[rustfmt::skip]\npub const MAP: ::phf::Map<u32, u32> = "));
+        assert_eq!(1, source.matches("#[rustfmt::skip]").count());
+
+        let mut builder = OrderedSet::new();
+        builder.rustfmt_skip().entry(1u32);
+        let source = builder.build().as_const_item("SET", "u32").to_string();
+        assert!(source.starts_with("#[rustfmt::skip]\npub const SET: "));
+    }
+
+    #[test]
+    fn case_insensitive_str() {
+        let mut builder = Map::new();
+        builder
+            .case_insensitive_str()
+            .entry("Content-Type", "0")
+            .entry("ACCEPT", "1")
+            .compact();
+        let source = builder.build().to_string();
+        assert!(source.contains("(\"content-type\", 0)"));
+        assert!(source.contains("(\"accept\", 1)"));
+        assert_eq!(
+            "::phf::SortedMap { entries: &[(\"accept\", 1), (\"content-type\", 0)] }",
+            builder.build_binary_search().to_string()
+        );
+
+        builder.entry("Accept", "2");
+        match builder.try_build() {
+            Err(BuildError::DuplicateKey {
+                index,
+                first_index,
+                key,
+            }) => {
+                assert_eq!(2, index);
+                assert_eq!(1, first_index);
+                assert_eq!("\"accept\"", key);
+            }
+            _ => panic!("expected a duplicate key error"),
+        }
+    }
+
+    #[test]
+    fn value_prefix() {
+        let mut builder = Map::new();
+        builder
+            .value_prefix("crate::Keyword")
+            .entry("loop", "Loop")
+            .compact();
+        let source = builder.build().to_string();
+        assert!(source.ends_with("entries: &[(\"loop\", crate::Keyword::Loop)] }"));
+        assert_eq!(
+            Some("Loop"),
+            builder.entries().next().map(|(_, v)| v).as_deref()
+        );
+
+        let mut builder = OrderedMap::new();
+        builder
+            .value_prefix("self")
+            .entry(1u32, "A")
+            .entry(2u32, "B");
+        let source = builder.build().to_string();
+        assert!(source.contains("(1, self::A),\n        (2, self::B),"));
+    }
+
+    #[test]
+    fn into_set() {
+        let mut builder = Map::new();
+        builder.entry("a", "1").entry("b", "2").entry("c", "3");
+        let map = builder.build();
+        let key = map.state.key;
+        let set = map.into_set();
+        assert_eq!(key, set.inner.state.key);
+
+        let mut expected = Set::new();
+        expected.entries_from(["a", "b", "c"]);
+        assert_eq!(expected.build().to_string(), set.to_string());
+    }
+
+    #[test]
+    fn with_len_const() {
+        let mut builder = Map::new();
+        builder
+            .with_len_const("MAP_LEN")
+            .dedup_last_wins()
+            .entry(1u32, "2")
+            .entry(2u32, "3")
+            .entry(1u32, "4");
+        let built = builder.build();
+        assert!(!built.to_string().contains("MAP_LEN"));
+
+        let source = built.as_const_item("MAP", "u32", "u32").to_string();
+        assert!(source.starts_with("pub const MAP_LEN: usize = 2;\npub const MAP: "));
+
+        let mut builder = Set::new();
+        builder.with_len_const("SET_LEN").rustfmt_skip().entry(1u32);
+        let source = builder
+            .build()
+            .as_static_item("SET", "u32")
+            .vis("")
+            .to_string();
+        assert!(source.starts_with("const SET_LEN: usize = 1;\n#[rustfmt::skip]\nstatic SET: "));
+    }
+
+    #[test]
+    fn static_item() {
+        let mut builder = Map::new();
+        builder.entry(1u32, "2");
+        let built = builder.build();
+        let source = built.as_static_item("MAP", "u32", "u32").to_string();
+        assert!(source.starts_with("pub static MAP: ::phf::Map<u32, u32> = ::phf::Map {"));
+        assert!(source.ends_with("};"));
+
+        let source = built.as_const_item("MAP", "u32", "u32").vis("").to_string();
+        assert!(source.starts_with("const MAP: "));
+
+        let mut builder = OrderedMap::new();
+        builder.entry(1u32, "2");
+        let built = builder.build();
+        let source = built
+            .as_static_item("MAP", "u32", "u32")
+            .vis("pub(super)")
+            .to_string();
+        assert!(source.starts_with("pub(super) static MAP: ::phf::OrderedMap<u32, u32> = "));
+    }
+
+    #[test]
+    #[cfg(feature = "proc-macro")]
+    fn to_token_stream() {
+        let mut builder = Map::new();
+        builder.entry("a", "1 + 1");
+        let tokens = builder.build().to_token_stream().to_string();
+        assert!(tokens.starts_with(":: phf :: Map {"));
+        assert!(tokens.contains("(\"a\" , 1 + 1)"));
+
+        let mut builder = OrderedSet::new();
+        builder.narrow_indices().entry(1u32);
+        let tokens = builder.build().to_token_stream().to_string();
+        assert!(tokens.starts_with(":: phf :: OrderedSet { map : :: phf :: OrderedMap {"));
+        assert!(tokens.contains("idxs : & [0u8]"));
+    }
+
+    #[test]
+    #[cfg(feature = "proc-macro")]
+    #[should_panic(expected = "unable to tokenize `)`")]
+    fn to_token_stream_invalid_value() {
+        let mut builder = Map::new();
+        builder.entry(1u32, ")");
+        builder.build().to_token_stream();
+    }
+
+    #[test]
+    fn char_keys() {
+        assert_eq!("'a'", Delegate('a').to_string());
+        assert_eq!("'\\''", Delegate('\'').to_string());
+        assert_eq!("'\\\\'", Delegate('\\').to_string());
+        assert_eq!("'\\n'", Delegate('\n').to_string());
+        assert_eq!("'\\u{1f600}'", Delegate('\u{1f600}').to_string());
+    }
+
+    #[test]
+    fn wide_int_keys() {
+        assert_eq!(
+            "340282366920938463463374607431768211455u128",
+            Delegate(u128::MAX).to_string()
+        );
+        assert_eq!(
+            "-170141183460469231731687303715884105728i128",
+            Delegate(i128::MIN).to_string()
+        );
+    }
+
+    #[test]
+    fn byte_array_keys() {
+        assert_eq!("[0x00, 0x7f, 0xff]", Delegate([0u8, 127, 255]).to_string());
+        assert_eq!("[]", Delegate([0u8; 0]).to_string());
+        assert_eq!("[0, 1]", Delegate([0u16, 1]).to_string());
+    }
+
+    #[test]
+    fn ip_addr_keys() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        assert_eq!(
+            "::std::net::Ipv4Addr::new(192, 168, 0, 1)",
+            Delegate(Ipv4Addr::new(192, 168, 0, 1)).to_string()
+        );
+        assert_eq!(
+            "::std::net::IpAddr::V6(::std::net::Ipv6Addr::new(0x2001, 0xdb8, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1))",
+            Delegate(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))).to_string()
+        );
+    }
+
+    #[test]
+    fn float_keys() {
+        assert_eq!("1.5f64", Delegate(1.5f64).to_string());
+        assert_eq!("-0.0f32", Delegate(-0.0f32).to_string());
+        assert_eq!("1e300f64", Delegate(1e300f64).to_string());
+        assert_eq!(
+            "::core::primitive::f64::NAN",
+            Delegate(f64::NAN).to_string()
+        );
+        assert_eq!(Err(phf_shared::FmtConstError), f64::NAN.check_fmt_const());
+        assert_eq!(Err(phf_shared::FmtConstError), f64::NAN.try_fmt_const());
+        assert_eq!(Ok("1.5f32".to_string()), 1.5f32.try_fmt_const());
+        assert_eq!(
+            "::core::primitive::f32::NEG_INFINITY",
+            Delegate(f32::NEG_INFINITY).to_string()
+        );
+
+        for value in [0.1f64, -0.0, f64::MIN_POSITIVE, f64::MAX, 5e-324, 1.0 / 3.0] {
+            let literal = Delegate(value).to_string();
+            let parsed = literal.trim_end_matches("f64").parse::<f64>().unwrap();
+            assert_eq!(value.to_bits(), parsed.to_bits());
+        }
+    }
+
+    #[test]
+    fn narrow_indices() {
+        assert_eq!("u8", smallest_index_type(0));
+        assert_eq!("u8", smallest_index_type(256));
+        assert_eq!("u16", smallest_index_type(257));
+        assert_eq!("u16", smallest_index_type(65536));
+        assert_eq!("u32", smallest_index_type(65537));
+
+        let mut builder = OrderedMap::new();
+        builder.compact().narrow_indices().entry(1u32, "\"a\"");
+        let built = builder.build();
+        assert_eq!("u8", built.index_type());
+        assert!(built.to_string().contains("idxs: &[0u8]"));
+        let item = built.as_const_item("MAP", "u32", "&str").to_string();
+        assert!(item.starts_with("pub const MAP: ::phf::OrderedMap<u32, &str, u8> = "));
+
+        let mut builder = OrderedSet::new();
+        builder.entry(1u32);
+        assert_eq!("usize", builder.build().index_type());
+    }
+
+    #[test]
+    fn compact() {
+        let mut builder = OrderedMap::new();
+        builder.compact().entry(1u32, "\"a\"");
+        let source = builder.build().to_string();
+        assert!(!source.contains('\n'));
+        assert!(source.starts_with("::phf::OrderedMap { key: "));
+        assert!(source.ends_with(", idxs: &[0], entries: &[(1, \"a\")] }"));
+    }
+
+    #[test]
+    fn indent() {
+        let mut builder = Map::new();
+        builder.indent(2).entry(1u32, "\"a\"");
+        let source = builder.build().to_string();
+        let lines = source.lines().collect::<Vec<_>>();
+        assert_eq!("::phf::Map {", lines[0]);
+        assert!(lines[1].starts_with("  key: "));
+        assert_eq!("  disps: &[", lines[2]);
+        assert_eq!("    (0, 0),", lines[3]);
+        assert_eq!("  ],", lines[4]);
+        assert_eq!("  entries: &[", lines[5]);
+        assert_eq!("    (1, \"a\"),", lines[6]);
+        assert_eq!("  ],", lines[7]);
+        assert_eq!("}", lines[8]);
+    }
+
+    // The output depends on the version of rustfmt on `PATH`, so this is
+    // only run by CI with the rustfmt used for the workspace's own check.
+    #[test]
+    #[ignore = "requires rustfmt on PATH"]
+    fn indent_rustfmt_round_trip() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        fn rustfmt(source: &str) -> String {
+            let mut rustfmt = Command::new("rustfmt")
+                .args(["--edition", "2021", "--emit", "stdout"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("failed to run rustfmt");
+            rustfmt
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(source.as_bytes())
+                .unwrap();
+            let output = rustfmt.wait_with_output().unwrap();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(output.status.success(), "{}", stderr);
+            String::from_utf8(output.stdout).unwrap()
+        }
+
+        let mut builder = Map::new();
+        builder.indent(4);
+        for i in 0..50u32 {
+            builder.entry(i, (i * 10).to_string());
+        }
+        let source = format!("{}\n", builder.build().as_static_item("MAP", "u32", "u32"));
+        assert_eq!(source, rustfmt(&source));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key `\"a\"`")]
+    fn build_duplicate_key() {
+        let mut builder = Set::new();
+        builder.entry("a").entry("a");
+        builder.build();
+    }
 }
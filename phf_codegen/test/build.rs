@@ -1,11 +1,18 @@
 use std::env;
 use std::fs::File;
 use std::io::{self, BufWriter, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::num::{NonZeroI8, NonZeroU32};
 use std::path::Path;
 
 use uncased::UncasedStr;
 use unicase::{Ascii, UniCase};
 
+#[path = "src/float_key.rs"]
+mod float_key;
+
+use float_key::FloatKey;
+
 fn main() -> io::Result<()> {
     let file = Path::new(&env::var("OUT_DIR").unwrap()).join("codegen.rs");
     let mut file = BufWriter::new(File::create(&file)?);
@@ -14,6 +21,7 @@ fn main() -> io::Result<()> {
         &mut file,
         "static MAP: ::phf::Map<u32, &'static str> = \n{};",
         phf_codegen::Map::new()
+            .validate_values()
             .entry(1u32, "\"a\"")
             .entry(2u32, "\"b\"")
             .entry(3u32, "\"c\"")
@@ -43,7 +51,7 @@ fn main() -> io::Result<()> {
     writeln!(
         &mut file,
         "static ORDERED_SET: ::phf::OrderedSet<u32> = \n{};",
-        phf_codegen::OrderedSet::new()
+        phf_codegen::OrderedSet::with_capacity(3)
             .entry(1u32)
             .entry(2u32)
             .entry(3u32)
@@ -53,7 +61,7 @@ fn main() -> io::Result<()> {
     writeln!(
         &mut file,
         "static STR_KEYS: ::phf::Map<&'static str, u32> = \n{};",
-        phf_codegen::Map::new()
+        phf_codegen::Map::with_capacity(3)
             .entry("a", "1")
             .entry("b", "2")
             .entry("c", "3")
@@ -122,6 +130,417 @@ fn main() -> io::Result<()> {
             .build()
     )?;
 
+    writeln!(
+        &mut file,
+        "static BYTE_KEYS_SET: ::phf::Set<&[u8]> = \n{};",
+        phf_codegen::Set::byte_keys()
+            .entry(b"foo")
+            .entry(b"quux")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static BYTE_KEYS_ORDERED_MAP: ::phf::OrderedMap<&[u8], u32> = \n{};",
+        phf_codegen::OrderedMap::byte_keys()
+            .entry(b"foo", "0")
+            .entry(b"quux", "1")
+            .build()
+    )?;
+
+    // Test streaming output through `write_to`
+    writeln!(
+        &mut file,
+        "static WRITE_TO_MAP: ::phf::Map<&'static str, u32> = "
+    )?;
+    phf_codegen::Map::new()
+        .entry("one", "1")
+        .entry("two", "2")
+        .build()
+        .write_to(&mut file)?;
+    writeln!(&mut file, ";")?;
+
+    // Test last-wins deduplication
+    writeln!(
+        &mut file,
+        "static DEDUP_ORDERED_MAP: ::phf::OrderedMap<&'static str, u32> = \n{};",
+        phf_codegen::OrderedMap::new()
+            .dedup_last_wins()
+            .entry("a", "1")
+            .entry("b", "2")
+            .entry("a", "3")
+            .entry("c", "4")
+            .build()
+    )?;
+
+    // Test `const` items
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .entry("a", "1")
+            .entry("b", "2")
+            .build()
+            .as_const_item("CONST_MAP", "&'static str", "u32")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::OrderedSet::new()
+            .entry(1u32)
+            .entry(2u32)
+            .build()
+            .as_const_item("CONST_ORDERED_SET", "u32")
+    )?;
+
+    // Test `static` items
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .entry("a", "1")
+            .entry("b", "2")
+            .build()
+            .as_static_item("STATIC_MAP", "&'static str", "u32")
+            .vis("pub(crate)")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::OrderedSet::new()
+            .entry(1u32)
+            .entry(2u32)
+            .build()
+            .as_static_item("STATIC_ORDERED_SET", "u32")
+            .vis("")
+    )?;
+
+    // Test token stream output
+    writeln!(
+        &mut file,
+        "static TOKEN_MAP: ::phf::Map<u32, &'static str> = {};",
+        phf_codegen::Map::new()
+            .entry(1u32, "\"a\"")
+            .entry(2u32, "\"b\"")
+            .build()
+            .to_token_stream()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static TOKEN_ORDERED_SET: ::phf::OrderedSet<u32> = {};",
+        phf_codegen::OrderedSet::new()
+            .entry(3u32)
+            .entry(1u32)
+            .build()
+            .to_token_stream()
+    )?;
+
+    // Test lazily produced values
+    let mut lazy_map = phf_codegen::Map::new();
+    for i in 0..100u32 {
+        lazy_map.entry_with(i, move || format!("{:?}", format!("Entry({})", i)));
+    }
+    writeln!(
+        &mut file,
+        "static LAZY_MAP: ::phf::Map<u32, &'static str> = {};",
+        lazy_map.build()
+    )?;
+
+    // Test raw key expressions
+    writeln!(
+        &mut file,
+        "static RAW_KEY_MAP: ::phf::Map<&'static str, u32> = {};",
+        phf_codegen::Map::new()
+            .entry(phf_codegen::RawKey::new("loop", "LOOP"), "1")
+            .entry(phf_codegen::RawKey::new("fn", "FN"), "2")
+            .build()
+    )?;
+
+    // Test NonZero keys
+    let mut nonzero_map = phf_codegen::Map::new();
+    for i in 1..=10u32 {
+        nonzero_map.entry(NonZeroU32::new(i).unwrap(), i.to_string());
+    }
+    nonzero_map.entry(NonZeroU32::new(u32::MAX).unwrap(), "0");
+    writeln!(
+        &mut file,
+        "static NONZERO_MAP: ::phf::Map<::core::num::NonZeroU32, u32> = {};",
+        nonzero_map.build()
+    )?;
+    writeln!(
+        &mut file,
+        "static NONZERO_SIGNED_SET: ::phf::Set<::core::num::NonZeroI8> = {};",
+        phf_codegen::Set::new()
+            .entry(NonZeroI8::new(i8::MIN).unwrap())
+            .entry(NonZeroI8::new(-1).unwrap())
+            .entry(NonZeroI8::new(i8::MAX).unwrap())
+            .build()
+    )?;
+
+    // Test case-insensitive string keys
+    writeln!(
+        &mut file,
+        "static CASE_INSENSITIVE_MAP: ::phf::Map<&'static str, u32> = {};",
+        phf_codegen::Map::new()
+            .case_insensitive_str()
+            .entry("Content-Type", "0")
+            .entry("Accept", "1")
+            .build()
+    )?;
+
+    // Test dispatch functions
+    let mut dispatcher = phf_codegen::Dispatcher::new();
+    dispatcher.entries_from(["fn", "let", "loop", "match", "while"]);
+    writeln!(
+        &mut file,
+        "{}",
+        dispatcher.build().as_fn("keyword", "str", "&str")
+    )?;
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Dispatcher::<u32>::new()
+            .build()
+            .as_fn("empty_dispatch", "u32", "u32")
+            .vis("")
+    )?;
+
+    writeln!(
+        &mut file,
+        "static ANNOTATED_MAP: ::phf::Map<&'static str, u32> = {};",
+        phf_codegen::Map::new()
+            .annotate_entries()
+            .entry("a\nb", "1")
+            .entry("c", "2")
+            .build()
+    )?;
+
+    let mut nested_map = phf_codegen::Map::new();
+    for (lang, names) in [
+        ("en", ["Germany", "France"]),
+        ("de", ["Deutschland", "Frankreich"]),
+    ] {
+        let mut inner = phf_codegen::Map::new();
+        inner
+            .entry("DE", format!("\"{}\"", names[0]))
+            .entry("FR", format!("\"{}\"", names[1]));
+        nested_map.entry_nested(lang, &inner.build(), "&str", "&str");
+    }
+    writeln!(
+        &mut file,
+        "{}",
+        nested_map
+            .build()
+            .as_static_item("NESTED_MAP", "&str", "::phf::Map<&str, &str>")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .entry("GB", "\"United Kingdom\"")
+            .build()
+            .as_const_item("EN_NAMES", "&str", "&str")
+    )?;
+    let mut fr_names = phf_codegen::Map::new();
+    fr_names.entry("GB", "\"Royaume-Uni\"");
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .value_prefix("self")
+            .entry("en", "EN_NAMES")
+            .entry_nested("fr", &fr_names.build(), "&str", "&str")
+            .build()
+            .as_static_item("PREFIXED_NESTED_MAP", "&str", "::phf::Map<&str, &str>")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .static_attrs(&["#[cfg_attr(target_os = \"linux\", link_section = \".rodata.phf\")]"])
+            .entry("a", "1")
+            .entry("b", "2")
+            .build()
+            .as_static_item("SECTION_MAP", "&str", "u32")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .entry("a", "1")
+            .entry("b", "2")
+            .entry("c", "3")
+            .build_owned_arrays()
+            .as_static_item("ARRAY_MAP", "&str", "u32")
+    )?;
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::<u32>::new()
+            .build_owned_arrays()
+            .as_const_item("EMPTY_ARRAY_MAP", "u32", "u32")
+    )?;
+
+    for len in 0..=2u32 {
+        let mut builder = phf_codegen::Map::new();
+        for key in 0..len {
+            builder.entry(key, (key * 10).to_string());
+        }
+        writeln!(
+            &mut file,
+            "{}",
+            builder
+                .build()
+                .as_static_item(&format!("SIZE_{}_MAP", len), "u32", "u32")
+        )?;
+    }
+
+    writeln!(
+        &mut file,
+        "{}",
+        phf_codegen::Map::new()
+            .entry("shared", "0")
+            .entry_cfg("unix", "1", "unix")
+            .entry_cfg("platform", "2", "unix")
+            .entry_cfg("platform", "3", "not(unix)")
+            .with_len_const("CFG_MAP_LEN")
+            .build_cfg()
+            .as_static_item("CFG_MAP", "&str", "u32")
+    )?;
+
+    writeln!(
+        &mut file,
+        "static FLOAT_MAP: ::phf::Map<FloatKey, &'static str> = \n{};",
+        phf_codegen::Map::new()
+            .entry(FloatKey::new(-0.0), "\"zero\"")
+            .entry(FloatKey::new(1.5), "\"one and a half\"")
+            .entry(FloatKey::new(f64::INFINITY), "\"infinity\"")
+            .build()
+    )?;
+
+    // Test binary search output
+    let mut sorted_map = phf_codegen::Map::new();
+    for (i, word) in ["delta", "alpha", "charlie", "bravo"].iter().enumerate() {
+        sorted_map.entry(*word, i.to_string());
+    }
+    writeln!(
+        &mut file,
+        "{}",
+        sorted_map
+            .build_binary_search()
+            .as_static_item("SORTED_MAP", "&'static str", "usize")
+    )?;
+
+    // Test compact output
+    writeln!(
+        &mut file,
+        "static COMPACT_MAP: ::phf::Map<u32, &'static str> = {};",
+        phf_codegen::Map::new()
+            .compact()
+            .entry(1u32, "\"a\"")
+            .entry(2u32, "\"b\"")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static COMPACT_ORDERED_SET: ::phf::OrderedSet<u32> = {};",
+        phf_codegen::OrderedSet::new()
+            .compact()
+            .entry(1u32)
+            .entry(2u32)
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static CHAR_KEYS: ::phf::Map<char, u32> = \n{};",
+        phf_codegen::Map::new()
+            .entry('a', "0")
+            .entry('\'', "1")
+            .entry('\\', "2")
+            .entry('\n', "3")
+            .entry('\u{1f600}', "4")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static U128_KEYS: ::phf::Map<u128, u32> = \n{};",
+        phf_codegen::Map::new()
+            .entry(0u128, "0")
+            .entry(1u128 << 64, "1")
+            .entry(u128::MAX, "2")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static I128_KEYS: ::phf::Set<i128> = \n{};",
+        phf_codegen::Set::new()
+            .entry(i128::MIN)
+            .entry(-1i128)
+            .entry(i128::MAX)
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static FINGERPRINTS: ::phf::Map<[u8; 16], &'static str> = \n{};",
+        phf_codegen::Map::new()
+            .entry([0u8; 16], "\"zero\"")
+            .entry([0xffu8; 16], "\"ones\"")
+            .entry(*b"0123456789abcdef", "\"digits\"")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static IPV4_KEYS: ::phf::Map<::std::net::Ipv4Addr, &'static str> = \n{};",
+        phf_codegen::Map::new()
+            .entry(Ipv4Addr::LOCALHOST, "\"localhost\"")
+            .entry(Ipv4Addr::new(192, 168, 0, 1), "\"router\"")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static IP_KEYS: ::phf::Set<::std::net::IpAddr> = \n{};",
+        phf_codegen::Set::new()
+            .entry(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .entry(IpAddr::V6(Ipv6Addr::LOCALHOST))
+            .entry(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)))
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static NARROW_ORDERED_MAP: ::phf::OrderedMap<u32, u32, u16> = \n{};",
+        (0..1000u32)
+            .fold(&mut phf_codegen::OrderedMap::new(), |builder, i| {
+                builder.entry(i, i.wrapping_mul(7).to_string())
+            })
+            .narrow_indices()
+            .build()
+    )?;
+
+    let mut narrow_set = phf_codegen::OrderedSet::new();
+    narrow_set.narrow_indices().entry("a").entry("b").entry("c");
+    let narrow_set = narrow_set.build();
+    writeln!(
+        &mut file,
+        "static NARROW_ORDERED_SET: ::phf::OrderedSet<&'static str, {}> = \n{};",
+        narrow_set.index_type(),
+        narrow_set
+    )?;
+
     // Test FromIterator implementation
     writeln!(
         &mut file,
@@ -132,6 +551,43 @@ fn main() -> io::Result<()> {
             .build()
     )?;
 
+    // Test Extend implementation
+    let mut extend_set = phf_codegen::Set::new();
+    extend_set.extend(["one", "two"]);
+    extend_set.extend(vec!["three"]);
+    writeln!(
+        &mut file,
+        "static EXTEND_SET: ::phf::Set<&'static str> = \n{};",
+        extend_set.build()
+    )?;
+
+    // Test header comments
+    writeln!(
+        &mut file,
+        "static HEADER_MAP: ::phf::Map<&'static str, u32> = {};",
+        phf_codegen::Map::new()
+            .header("This map is generated.")
+            .entry("a", "1")
+            .build()
+    )?;
+
+    // Test inverse maps
+    let mut names = phf_codegen::Map::new();
+    names
+        .entry("one", "1")
+        .entry("two", "2")
+        .entry("three", "3");
+    writeln!(
+        &mut file,
+        "static NAMES: ::phf::Map<&'static str, u32> = \n{};",
+        names.build()
+    )?;
+    writeln!(
+        &mut file,
+        "static NAMES_INVERSE: ::phf::Map<u32, &'static str> = \n{};",
+        names.inverse(|v| v.parse::<u32>().unwrap()).build()
+    )?;
+
     // Test tuple keys for Map
     writeln!(
         &mut file,
@@ -165,6 +621,26 @@ fn main() -> io::Result<()> {
             .build()
     )?;
 
+    writeln!(
+        &mut file,
+        "static BOOL_KEYS: ::phf::Map<bool, &'static str> = \n{};",
+        phf_codegen::Map::new()
+            .entry(true, "\"yes\"")
+            .entry(false, "\"no\"")
+            .build()
+    )?;
+
+    // Test composite tuple and array keys
+    writeln!(
+        &mut file,
+        "static COMPOSITE_KEYS: ::phf::Map<(u16, u8, [u16; 2], bool), u32> = \n{};",
+        phf_codegen::Map::new()
+            .entry((1u16, 2u8, [3u16, 4], true), "1")
+            .entry((1u16, 2u8, [3u16, 4], false), "2")
+            .entry((0xffffu16, 0u8, [0u16, 0xffff], true), "3")
+            .build()
+    )?;
+
     // Test mixed type tuple keys
     writeln!(
         &mut file,
@@ -0,0 +1,54 @@
+//! A canonicalized `f64` key, shared by the build script and the tests.
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use phf_shared::{FmtConst, PhfBorrow, PhfHash};
+
+/// An `f64` key which implements `Eq` by only ever holding canonical values.
+///
+/// `-0.0` is stored as `0.0`, so that the two are the same key, and NaNs are
+/// rejected. Hashing and printing then delegate to the `f64` implementations
+/// of `PhfHash` and `FmtConst`.
+#[derive(Debug, Clone, Copy)]
+pub struct FloatKey(pub f64);
+
+impl FloatKey {
+    pub fn new(value: f64) -> Self {
+        assert!(!value.is_nan(), "NaN cannot be a key");
+        FloatKey(if value == 0.0 { 0.0 } else { value })
+    }
+}
+
+impl PartialEq for FloatKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for FloatKey {}
+
+impl Hash for FloatKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state)
+    }
+}
+
+impl PhfHash for FloatKey {
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        self.0.phf_hash(state)
+    }
+}
+
+impl FmtConst for FloatKey {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FloatKey(")?;
+        self.0.fmt_const(f)?;
+        f.write_str(")")
+    }
+}
+
+impl PhfBorrow<FloatKey> for FloatKey {
+    fn borrow(&self) -> &FloatKey {
+        self
+    }
+}
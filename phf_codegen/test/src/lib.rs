@@ -1,8 +1,13 @@
+#[cfg(test)]
+mod float_key;
+
 #[cfg(test)]
 mod test {
     use uncased::UncasedStr;
     use unicase::{Ascii, UniCase};
 
+    use crate::float_key::FloatKey;
+
     include!(concat!(env!("OUT_DIR"), "/codegen.rs"));
 
     #[test]
@@ -99,6 +104,66 @@ mod test {
         assert_eq!(2, ARRAY_KEYS[b"baz"]);
     }
 
+    #[test]
+    fn char_keys() {
+        assert_eq!(0, CHAR_KEYS[&'a']);
+        assert_eq!(1, CHAR_KEYS[&'\'']);
+        assert_eq!(2, CHAR_KEYS[&'\\']);
+        assert_eq!(3, CHAR_KEYS[&'\n']);
+        assert_eq!(4, CHAR_KEYS[&'\u{1f600}']);
+        assert!(!CHAR_KEYS.contains_key(&'b'));
+    }
+
+    #[test]
+    fn wide_int_keys() {
+        assert_eq!(0, U128_KEYS[&0]);
+        assert_eq!(1, U128_KEYS[&(1 << 64)]);
+        assert_eq!(2, U128_KEYS[&u128::MAX]);
+        assert!(!U128_KEYS.contains_key(&1));
+
+        assert!(I128_KEYS.contains(&i128::MIN));
+        assert!(I128_KEYS.contains(&-1));
+        assert!(I128_KEYS.contains(&i128::MAX));
+        assert!(!I128_KEYS.contains(&0));
+    }
+
+    #[test]
+    fn fingerprint_keys() {
+        assert_eq!("zero", FINGERPRINTS[&[0; 16]]);
+        assert_eq!("ones", FINGERPRINTS[&[0xff; 16]]);
+        assert_eq!("digits", FINGERPRINTS[b"0123456789abcdef"]);
+        assert_eq!(None, FINGERPRINTS.get(&[1; 16]));
+    }
+
+    #[test]
+    fn ip_addr_keys() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        assert_eq!("localhost", IPV4_KEYS[&Ipv4Addr::LOCALHOST]);
+        assert_eq!("router", IPV4_KEYS[&Ipv4Addr::new(192, 168, 0, 1)]);
+        assert_eq!(None, IPV4_KEYS.get(&Ipv4Addr::new(10, 0, 0, 1)));
+
+        assert!(IP_KEYS.contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(IP_KEYS.contains(&IpAddr::V6(Ipv6Addr::LOCALHOST)));
+        assert!(IP_KEYS.contains(&IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))));
+        assert!(!IP_KEYS.contains(&IpAddr::V6(Ipv6Addr::UNSPECIFIED)));
+    }
+
+    #[test]
+    fn narrow_indices() {
+        for i in 0..1000u32 {
+            assert_eq!(Some(&i.wrapping_mul(7)), NARROW_ORDERED_MAP.get(&i));
+            assert_eq!(Some(i as usize), NARROW_ORDERED_MAP.get_index(&i));
+        }
+        assert_eq!(None, NARROW_ORDERED_MAP.get(&1000));
+
+        assert_eq!(Some(1), NARROW_ORDERED_SET.get_index("b"));
+        assert_eq!(
+            vec!["a", "b", "c"],
+            NARROW_ORDERED_SET.iter().copied().collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn byte_str_keys() {
         // slicing is required unless the key type is fixed-size
@@ -108,6 +173,14 @@ mod test {
         assert_eq!(3, BYTE_STR_KEYS[&b"quux"[..]]);
     }
 
+    #[test]
+    fn byte_keys() {
+        assert!(BYTE_KEYS_SET.contains(&b"foo"[..]));
+        assert!(BYTE_KEYS_SET.contains(&b"quux"[..]));
+        assert!(!BYTE_KEYS_SET.contains(&b"bar"[..]));
+        assert_eq!(Some(1), BYTE_KEYS_ORDERED_MAP.get_index(&b"quux"[..]));
+    }
+
     #[test]
     fn empty_map() {
         assert_eq!(None, EMPTY.get(&1));
@@ -118,6 +191,204 @@ mod test {
         assert_eq!(None, EMPTY_ORDERED.get(&1));
     }
 
+    #[test]
+    fn write_to_map() {
+        assert_eq!(1, WRITE_TO_MAP["one"]);
+        assert_eq!(2, WRITE_TO_MAP["two"]);
+        assert!(!WRITE_TO_MAP.contains_key("three"));
+    }
+
+    #[test]
+    fn dedup_ordered_map() {
+        assert_eq!(3, DEDUP_ORDERED_MAP.len());
+        assert_eq!(3, DEDUP_ORDERED_MAP["a"]);
+        assert_eq!(2, DEDUP_ORDERED_MAP["b"]);
+        assert_eq!(4, DEDUP_ORDERED_MAP["c"]);
+        assert_eq!(
+            &["a", "b", "c"][..],
+            &DEDUP_ORDERED_MAP.keys().cloned().collect::<Vec<_>>()[..]
+        );
+    }
+
+    #[test]
+    fn const_items() {
+        const CONST_MAP_LEN: usize = CONST_MAP.len();
+        assert_eq!(2, CONST_MAP_LEN);
+        assert_eq!(1, CONST_MAP["a"]);
+        assert_eq!(2, CONST_MAP["b"]);
+
+        const CONST_ORDERED_SET_LEN: usize = CONST_ORDERED_SET.len();
+        assert_eq!(2, CONST_ORDERED_SET_LEN);
+        assert_eq!(Some(1), CONST_ORDERED_SET.get_index(&2));
+    }
+
+    #[test]
+    fn static_items() {
+        assert_eq!(1, STATIC_MAP["a"]);
+        assert_eq!(2, STATIC_MAP["b"]);
+        assert_eq!(Some(1), STATIC_ORDERED_SET.get_index(&2));
+    }
+
+    #[test]
+    fn token_stream() {
+        assert_eq!("a", TOKEN_MAP[&1]);
+        assert_eq!("b", TOKEN_MAP[&2]);
+        assert_eq!(Some(0), TOKEN_ORDERED_SET.get_index(&3));
+        assert_eq!(Some(1), TOKEN_ORDERED_SET.get_index(&1));
+    }
+
+    #[test]
+    fn lazy_values() {
+        for i in 0..100u32 {
+            assert_eq!(format!("Entry({})", i), LAZY_MAP[&i]);
+        }
+    }
+
+    const LOOP: &str = "loop";
+    const FN: &str = "fn";
+
+    #[test]
+    fn raw_keys() {
+        assert_eq!(1, RAW_KEY_MAP["loop"]);
+        assert_eq!(2, RAW_KEY_MAP["fn"]);
+        assert!(!RAW_KEY_MAP.contains_key("LOOP"));
+    }
+
+    #[test]
+    fn nonzero_keys() {
+        use core::num::{NonZeroI8, NonZeroU32};
+
+        for i in 1..=10u32 {
+            assert_eq!(i, NONZERO_MAP[&NonZeroU32::new(i).unwrap()]);
+        }
+        assert_eq!(0, NONZERO_MAP[&NonZeroU32::new(u32::MAX).unwrap()]);
+        assert!(!NONZERO_MAP.contains_key(&NonZeroU32::new(11).unwrap()));
+
+        assert_eq!(3, NONZERO_SIGNED_SET.len());
+        assert!(NONZERO_SIGNED_SET.contains(&NonZeroI8::new(i8::MIN).unwrap()));
+        assert!(NONZERO_SIGNED_SET.contains(&NonZeroI8::new(-1).unwrap()));
+        assert!(!NONZERO_SIGNED_SET.contains(&NonZeroI8::new(1).unwrap()));
+    }
+
+    #[test]
+    fn case_insensitive_str() {
+        for name in ["content-type", "Content-Type", "CONTENT-TYPE"] {
+            assert_eq!(
+                Some(&0),
+                CASE_INSENSITIVE_MAP.get(name.to_ascii_lowercase().as_str())
+            );
+        }
+        assert_eq!(1, CASE_INSENSITIVE_MAP["accept"]);
+        assert!(!CASE_INSENSITIVE_MAP.contains_key("Accept"));
+    }
+
+    #[test]
+    fn dispatcher() {
+        for (i, word) in ["fn", "let", "loop", "match", "while"].iter().enumerate() {
+            assert_eq!(Some(i), keyword(word));
+        }
+        assert_eq!(None, keyword("for"));
+        assert_eq!(None, keyword(&"loop".to_string()[1..]));
+        assert_eq!(None, empty_dispatch(&0));
+    }
+
+    #[test]
+    fn annotate_entries() {
+        assert_eq!(1, ANNOTATED_MAP["a\nb"]);
+        assert_eq!(2, ANNOTATED_MAP["c"]);
+    }
+
+    #[test]
+    fn entry_nested() {
+        assert_eq!(2, NESTED_MAP.len());
+        assert_eq!("France", NESTED_MAP["en"]["FR"]);
+        assert_eq!(Some(&"Deutschland"), NESTED_MAP["de"].get("DE"));
+        assert!(NESTED_MAP.get("fr").is_none());
+
+        assert_eq!("United Kingdom", PREFIXED_NESTED_MAP["en"]["GB"]);
+        assert_eq!("Royaume-Uni", PREFIXED_NESTED_MAP["fr"]["GB"]);
+    }
+
+    #[test]
+    fn static_attrs() {
+        assert_eq!(2, SECTION_MAP.len());
+        assert_eq!(1, SECTION_MAP["a"]);
+        assert_eq!(Some(&2), SECTION_MAP.get("b"));
+        assert_eq!(2, SECTION_MAP_ENTRIES.len());
+    }
+
+    #[test]
+    fn owned_arrays() {
+        assert_eq!(3, ARRAY_MAP.len());
+        assert_eq!(1, ARRAY_MAP["a"]);
+        assert_eq!(Some(&3), ARRAY_MAP.get("c"));
+        assert!(!ARRAY_MAP.contains_key("d"));
+        let mut keys = ARRAY_MAP.keys().copied().collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(vec!["a", "b", "c"], keys);
+
+        assert!(EMPTY_ARRAY_MAP.is_empty());
+        assert_eq!(None, EMPTY_ARRAY_MAP.get(&1));
+    }
+
+    #[test]
+    fn degenerate_sizes() {
+        assert!(SIZE_0_MAP.is_empty());
+        assert_eq!(None, SIZE_0_MAP.get(&0));
+
+        assert_eq!(1, SIZE_1_MAP.len());
+        assert_eq!(Some(&0), SIZE_1_MAP.get(&0));
+        assert_eq!(None, SIZE_1_MAP.get(&1));
+
+        assert_eq!(2, SIZE_2_MAP.len());
+        assert_eq!(Some(&0), SIZE_2_MAP.get(&0));
+        assert_eq!(Some(&10), SIZE_2_MAP.get(&1));
+        assert_eq!(None, SIZE_2_MAP.get(&2));
+    }
+
+    #[test]
+    fn cfg_map() {
+        assert_eq!(if cfg!(unix) { 3 } else { 2 }, CFG_MAP_LEN);
+        assert_eq!(Some(&0), CFG_MAP.get("shared"));
+        assert_eq!(cfg!(unix), CFG_MAP.contains_key("unix"));
+        assert_eq!(
+            Some(&if cfg!(unix) { 2 } else { 3 }),
+            CFG_MAP.get("platform")
+        );
+    }
+
+    #[test]
+    fn float_map() {
+        assert_eq!(Some(&"zero"), FLOAT_MAP.get(&FloatKey::new(0.0)));
+        assert_eq!(Some(&"zero"), FLOAT_MAP.get(&FloatKey::new(-0.0)));
+        assert_eq!(Some(&"one and a half"), FLOAT_MAP.get(&FloatKey::new(1.5)));
+        assert_eq!(
+            Some(&"infinity"),
+            FLOAT_MAP.get(&FloatKey::new(f64::INFINITY))
+        );
+        assert_eq!(None, FLOAT_MAP.get(&FloatKey::new(2.5)));
+    }
+
+    #[test]
+    fn binary_search() {
+        assert_eq!(4, SORTED_MAP.len());
+        assert_eq!(0, SORTED_MAP["delta"]);
+        assert_eq!(Some(&2), SORTED_MAP.get("charlie"));
+        assert!(!SORTED_MAP.contains_key("echo"));
+        assert_eq!(
+            vec!["alpha", "bravo", "charlie", "delta"],
+            SORTED_MAP.keys().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!("a", COMPACT_MAP[&1]);
+        assert_eq!("b", COMPACT_MAP[&2]);
+        assert!(COMPACT_ORDERED_SET.contains(&1));
+        assert_eq!(Some(1), COMPACT_ORDERED_SET.get_index(&2));
+    }
+
     #[test]
     fn from_iter_map() {
         assert_eq!(1, FROM_ITER_MAP["one"]);
@@ -126,6 +397,27 @@ mod test {
         assert!(!FROM_ITER_MAP.contains_key("four"));
     }
 
+    #[test]
+    fn extend_set() {
+        assert!(EXTEND_SET.contains("one"));
+        assert!(EXTEND_SET.contains("two"));
+        assert!(EXTEND_SET.contains("three"));
+        assert!(!EXTEND_SET.contains("four"));
+    }
+
+    #[test]
+    fn header_map() {
+        assert_eq!(1, HEADER_MAP["a"]);
+    }
+
+    #[test]
+    fn inverse_map() {
+        for (name, id) in &NAMES {
+            assert_eq!(name, &NAMES_INVERSE[id]);
+        }
+        assert_eq!(NAMES.len(), NAMES_INVERSE.len());
+    }
+
     #[test]
     fn tuple_map() {
         assert_eq!("first", TUPLE_MAP[&(1u32, "a")]);
@@ -153,6 +445,21 @@ mod test {
         assert!(!NESTED_TUPLE_MAP.contains_key(&((1u32, 2u32), "wrong")));
     }
 
+    #[test]
+    fn bool_keys() {
+        assert_eq!(2, BOOL_KEYS.len());
+        assert_eq!("yes", BOOL_KEYS[&true]);
+        assert_eq!("no", BOOL_KEYS[&false]);
+    }
+
+    #[test]
+    fn composite_keys() {
+        assert_eq!(1, COMPOSITE_KEYS[&(1, 2, [3, 4], true)]);
+        assert_eq!(2, COMPOSITE_KEYS[&(1, 2, [3, 4], false)]);
+        assert_eq!(3, COMPOSITE_KEYS[&(0xffff, 0, [0, 0xffff], true)]);
+        assert!(!COMPOSITE_KEYS.contains_key(&(1, 2, [4, 3], true)));
+    }
+
     #[test]
     fn mixed_tuple_map() {
         assert_eq!("value1", MIXED_TUPLE_MAP[&(true, 1u8, "test")]);
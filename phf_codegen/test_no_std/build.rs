@@ -0,0 +1,39 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+fn main() -> io::Result<()> {
+    let file = Path::new(&env::var("OUT_DIR").unwrap()).join("codegen.rs");
+    let mut file = BufWriter::new(File::create(&file)?);
+
+    writeln!(
+        &mut file,
+        "static STR_KEYS: ::phf::Map<&'static str, u32> = \n{};",
+        phf_codegen::Map::new()
+            .entry("loop", "1")
+            .entry("continue", "2")
+            .entry("break", "3")
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static BYTE_KEYS: ::phf::Set<&'static [u8]> = \n{};",
+        phf_codegen::Set::new()
+            .entry(&b"foo"[..])
+            .entry(&b"bar"[..])
+            .build()
+    )?;
+
+    writeln!(
+        &mut file,
+        "static CHAR_KEYS: ::phf::OrderedMap<char, f64> = \n{};",
+        phf_codegen::OrderedMap::new()
+            .entry('a', "1.0")
+            .entry('\u{10ffff}', "::core::primitive::f64::NAN")
+            .build()
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Checks that the source constructed by `phf_codegen` compiles in a
+//! `#![no_std]` crate.
+#![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+include!(concat!(env!("OUT_DIR"), "/codegen.rs"));
+
+pub fn str_key(key: &str) -> Option<u32> {
+    STR_KEYS.get(key).copied()
+}
+
+pub fn byte_key(key: &[u8]) -> bool {
+    BYTE_KEYS.contains(key)
+}
+
+pub fn char_key(key: char) -> Option<usize> {
+    CHAR_KEYS.get_index(&key)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn str_keys() {
+        assert_eq!(Some(1), str_key("loop"));
+        assert_eq!(Some(3), str_key("break"));
+        assert_eq!(None, str_key("fn"));
+    }
+
+    #[test]
+    fn byte_keys() {
+        assert!(byte_key(b"foo"));
+        assert!(!byte_key(b"baz"));
+    }
+
+    #[test]
+    fn char_keys() {
+        assert_eq!(Some(1), char_key('\u{10ffff}'));
+        assert!(CHAR_KEYS[&'\u{10ffff}'].is_nan());
+        assert_eq!(None, char_key('b'));
+    }
+}
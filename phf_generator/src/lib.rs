@@ -3,7 +3,8 @@
 //! [phf]: https://docs.rs/phf
 
 #![doc(html_root_url = "https://docs.rs/phf_generator/0.13.1")]
-use std::iter;
+use std::error::Error;
+use std::fmt;
 
 use fastrand::Rng;
 use phf_shared::{HashKey, Hashes, PhfHash};
@@ -12,36 +13,679 @@ const DEFAULT_LAMBDA: usize = 5;
 
 const FIXED_SEED: u64 = 1234567890;
 
+// The largest average bucket size tried by `generate_hash_min_size`.
+const MAX_MIN_SIZE_LAMBDA: usize = 8;
+
+/// The number of hash keys tried before giving up on finding a PHF.
+///
+/// For well-behaved hash functions each attempt is overwhelmingly likely to
+/// succeed, so running out of attempts indicates degenerate input such as
+/// duplicate keys or a hash function mapping many keys to the same value.
+pub const MAX_ATTEMPTS: usize = 100;
+
+/// An error generating a PHF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GenError {
+    /// No PHF was found within the given number of attempts.
+    Unsolvable { attempts: usize },
+    /// There were more entries than can be indexed by a `u32`.
+    TooManyEntries { len: usize },
+    /// Two entries have identical hashes, as duplicate entries do, so no PHF
+    /// can separate them.
+    DuplicateHashes {
+        /// The index of the later of the two entries.
+        index: usize,
+        /// The index of the earlier of the two entries.
+        first_index: usize,
+    },
+}
+
+impl fmt::Display for GenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenError::Unsolvable { attempts } => {
+                write!(f, "no PHF found after {} attempts", attempts)
+            }
+            GenError::TooManyEntries { len } => {
+                write!(f, "{} entries exceeds the maximum of {}", len, u32::MAX)
+            }
+            GenError::DuplicateHashes { index, first_index } => write!(
+                f,
+                "entries at indices {} and {} have identical hashes",
+                first_index, index
+            ),
+        }
+    }
+}
+
+impl Error for GenError {}
+
+/// A generated PHF, in the layout read by `phf::Map` and the other `phf`
+/// types.
+///
+/// The key is hashed with `phf_shared::hash` using [`key`](Self::key), and
+/// `phf_shared::get_index` with [`disps`](Self::disps) gives the slot of the
+/// key. The fields are part of the public API of this crate and only change
+/// in a semver-incompatible release, but the [`disps`](Self::disps) and
+/// [`slot_map`](Self::slot_map) accessors are preferred by code which only
+/// reads them.
 pub struct HashState {
+    /// The key the entries are hashed with.
     pub key: HashKey,
+    /// The displacement pair of each bucket, indexed by `g % disps.len()`
+    /// for the hashes of a key.
     pub disps: Vec<(u32, u32)>,
+    /// The index into the input entries of the entry in each slot.
+    ///
+    /// There is one slot per entry, so this is a permutation of
+    /// `0..entries.len()`.
     pub map: Vec<usize>,
 }
 
+impl HashState {
+    /// Returns the displacement pair of each bucket.
+    pub fn disps(&self) -> &[(u32, u32)] {
+        &self.disps
+    }
+
+    /// Returns the index into the input entries of the entry in each slot.
+    pub fn slot_map(&self) -> &[usize] {
+        &self.map
+    }
+
+    /// Returns the largest component of any displacement pair, or 0 if there
+    /// are none.
+    ///
+    /// This is the smallest bound an integer type must have to store every
+    /// displacement.
+    pub fn max_disp(&self) -> u32 {
+        self.disps
+            .iter()
+            .map(|&(d1, d2)| d1.max(d2))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A source of the hash keys tried while generating a PHF.
+///
+/// Implement this to drive generation with a random number generator other
+/// than the `fastrand::Rng` seeded internally by this crate.
+pub trait RngLike {
+    /// Returns the next random `u64`.
+    fn next_u64(&mut self) -> u64;
+}
+
+impl RngLike for Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.u64(..)
+    }
+}
+
+impl<R: RngLike + ?Sized> RngLike for &mut R {
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+}
+
+/// Tuning parameters for PHF generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GenConfig {
+    /// The average number of entries per bucket.
+    ///
+    /// Larger values produce fewer displacement pairs, and so smaller output,
+    /// at the cost of slower generation. Defaults to 5.
+    pub lambda: usize,
+    /// The number of hash keys tried before giving up. Defaults to
+    /// [`MAX_ATTEMPTS`].
+    pub max_attempts: usize,
+}
+
+impl Default for GenConfig {
+    fn default() -> Self {
+        GenConfig {
+            lambda: DEFAULT_LAMBDA,
+            max_attempts: MAX_ATTEMPTS,
+        }
+    }
+}
+
+/// Statistics about the generation of a PHF.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct GenStats {
+    /// The number of hash keys tried, including the successful one.
+    pub attempts: usize,
+    /// The hash key that was chosen.
+    pub key: HashKey,
+    /// The number of slots in the table.
+    pub slots: usize,
+    /// The number of buckets, i.e. the number of displacement pairs.
+    pub buckets: usize,
+    /// The number of entries divided by the number of slots.
+    ///
+    /// The table has exactly one slot per entry, so this is always 1 for a
+    /// non-empty table.
+    pub load_factor: f64,
+    /// The largest displacement value used by any bucket.
+    pub max_disp: u32,
+    /// The distribution of bucket sizes under the chosen hash key.
+    ///
+    /// `bucket_sizes[n]` is the number of buckets holding exactly `n` entries.
+    /// Buckets are placed largest first, so a single oversized bucket is the
+    /// usual cause of a slow search.
+    pub bucket_sizes: Vec<usize>,
+}
+
+impl GenStats {
+    fn new(state: &HashState, attempts: usize, buckets: &[Bucket]) -> Self {
+        let slots = state.map.len();
+        let mut bucket_sizes = vec![];
+        for bucket in buckets {
+            let len = bucket.keys.len();
+            if bucket_sizes.len() <= len {
+                bucket_sizes.resize(len + 1, 0);
+            }
+            bucket_sizes[len] += 1;
+        }
+        GenStats {
+            attempts,
+            key: state.key,
+            slots,
+            buckets: state.disps.len(),
+            load_factor: if slots == 0 { 0.0 } else { 1.0 },
+            max_disp: state.max_disp(),
+            bucket_sizes,
+        }
+    }
+
+    /// Returns the number of entries in the largest bucket.
+    pub fn max_bucket_len(&self) -> usize {
+        self.bucket_sizes.len().saturating_sub(1)
+    }
+
+    /// Returns the mean number of entries per bucket.
+    pub fn mean_bucket_len(&self) -> f64 {
+        if self.buckets == 0 {
+            0.0
+        } else {
+            self.slots as f64 / self.buckets as f64
+        }
+    }
+}
+
+/// An event reported to the observer given to [`generate_hash_with_observer`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum GenEvent<'a> {
+    /// No PHF was found under the hash key `key`, which was the `attempt`th
+    /// tried. Another key will be tried unless the attempts are exhausted.
+    AttemptFailed { attempt: usize, key: HashKey },
+    /// A PHF was found. This is reported once, after any failed attempts.
+    Solved(&'a GenStats),
+}
+
+/// Generates a PHF for `entries`.
+///
+/// The output is deterministic: for a given version of this crate, the same
+/// entries always produce the same `HashState`.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts; see
+/// [`try_generate_hash`].
 pub fn generate_hash<H: PhfHash>(entries: &[H]) -> HashState {
-    generate_hash_with_hash_fn(entries, phf_shared::hash)
+    generate_hash_with_seed(entries, FIXED_SEED)
+}
+
+/// Like `generate_hash`, but derives the hash keys tried from `seed` rather
+/// than a fixed default.
+///
+/// The same entries and seed always produce the same `HashState`.
+pub fn generate_hash_with_seed<H: PhfHash>(entries: &[H], seed: u64) -> HashState {
+    try_generate(entries, phf_shared::hash, seed)
+        .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
 }
 
+/// Like `generate_hash`, but draws the hash keys tried from `rng` rather than
+/// the crate's internally seeded generator.
+///
+/// The result is deterministic if `rng` is.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts; see
+/// [`try_generate_hash_with_rng`].
+pub fn generate_hash_with_rng<H: PhfHash, R: RngLike>(entries: &[H], rng: R) -> HashState {
+    try_generate_hash_with_rng(entries, rng)
+        .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash_with_rng`, but returns an error instead of panicking if
+/// no PHF is found within [`MAX_ATTEMPTS`] attempts.
+pub fn try_generate_hash_with_rng<H: PhfHash, R: RngLike>(
+    entries: &[H],
+    rng: R,
+) -> Result<HashState, GenError> {
+    try_generate_with_rng(
+        &mut Generator::new(),
+        entries,
+        phf_shared::hash,
+        rng,
+        &GenConfig::default(),
+        &mut |_| {},
+    )
+    .map(|(state, _)| state)
+}
+
+/// Like `generate_hash`, but with the tuning parameters in `config`.
+///
+/// # Panics
+///
+/// Panics if `config.lambda` is 0, or if no PHF is found within
+/// `config.max_attempts` attempts.
+pub fn generate_hash_with_config<H: PhfHash>(entries: &[H], config: &GenConfig) -> HashState {
+    try_generate_with_stats(
+        &mut Generator::new(),
+        entries,
+        phf_shared::hash,
+        FIXED_SEED,
+        config,
+    )
+    .map(|(state, _)| state)
+    .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash`, but generates a PHF with fewer displacement pairs,
+/// and so a smaller constructed table.
+///
+/// Each displacement pair serves one bucket, so this uses a
+/// [`GenConfig::lambda`] of 8 rather than the default of 5, giving about 37%
+/// fewer pairs. For thousands of entries or more, CHD almost always succeeds
+/// on the first attempt at that lambda, so this is rarely slower than
+/// `generate_hash`. Smaller sets of entries can exhaust [`MAX_ATTEMPTS`]
+/// attempts, in which case each smaller lambda down to 5 is tried in turn.
+///
+/// # Panics
+///
+/// Panics if no PHF is found with any of the lambdas tried; see
+/// [`try_generate_hash_min_size`].
+pub fn generate_hash_min_size<H: PhfHash>(entries: &[H]) -> HashState {
+    try_generate_hash_min_size(entries).unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash_min_size`, but returns an error instead of panicking
+/// if no PHF is found with any of the lambdas tried.
+pub fn try_generate_hash_min_size<H: PhfHash>(entries: &[H]) -> Result<HashState, GenError> {
+    let mut generator = Generator::new();
+    let mut result = Err(GenError::Unsolvable { attempts: 0 });
+    for lambda in (DEFAULT_LAMBDA..=MAX_MIN_SIZE_LAMBDA).rev() {
+        let config = GenConfig {
+            lambda,
+            ..GenConfig::default()
+        };
+        result = try_generate_with_stats(
+            &mut generator,
+            entries,
+            phf_shared::hash,
+            FIXED_SEED,
+            &config,
+        );
+        match result {
+            // no lambda separates entries with identical hashes
+            Ok(_) | Err(GenError::DuplicateHashes { .. }) => break,
+            Err(_) => {}
+        }
+    }
+    result.map(|(state, _)| state)
+}
+
+/// Like `generate_hash`, but gives up and returns `None` after trying
+/// `max_attempts` hash keys.
+pub fn generate_hash_bounded<H: PhfHash>(entries: &[H], max_attempts: usize) -> Option<HashState> {
+    let config = GenConfig {
+        max_attempts,
+        ..GenConfig::default()
+    };
+    try_generate_with_stats(
+        &mut Generator::new(),
+        entries,
+        phf_shared::hash,
+        FIXED_SEED,
+        &config,
+    )
+    .map(|(state, _)| state)
+    .ok()
+}
+
+/// Like `generate_hash`, but also returns statistics about how the PHF was
+/// found.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts.
+pub fn generate_hash_with_stats<H: PhfHash>(entries: &[H]) -> (HashState, GenStats) {
+    try_generate_with_stats(
+        &mut Generator::new(),
+        entries,
+        phf_shared::hash,
+        FIXED_SEED,
+        &GenConfig::default(),
+    )
+    .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash`, but reports each failed attempt and the statistics of
+/// the solution to `observer` as generation proceeds.
+///
+/// This can be used to log slow generation, such as from a build script.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts; see
+/// [`try_generate_hash_with_observer`].
+pub fn generate_hash_with_observer<H, O>(entries: &[H], observer: O) -> HashState
+where
+    H: PhfHash,
+    O: FnMut(GenEvent<'_>),
+{
+    try_generate_hash_with_observer(entries, observer)
+        .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash_with_observer`, but returns an error instead of
+/// panicking if no PHF is found within [`MAX_ATTEMPTS`] attempts.
+pub fn try_generate_hash_with_observer<H, O>(
+    entries: &[H],
+    mut observer: O,
+) -> Result<HashState, GenError>
+where
+    H: PhfHash,
+    O: FnMut(GenEvent<'_>),
+{
+    try_generate_with_rng(
+        &mut Generator::new(),
+        entries,
+        phf_shared::hash,
+        Rng::with_seed(FIXED_SEED),
+        &GenConfig::default(),
+        &mut observer,
+    )
+    .map(|(state, _)| state)
+}
+
+/// Like `generate_hash`, but returns an error instead of panicking if no PHF
+/// is found within [`MAX_ATTEMPTS`] attempts.
+pub fn try_generate_hash<H: PhfHash>(entries: &[H]) -> Result<HashState, GenError> {
+    try_generate(entries, phf_shared::hash, FIXED_SEED)
+}
+
+/// Generates a PHF for `entries` using a custom hash function.
+///
+/// `hash_fn` computes the `Hashes` of an entry under the given hash key, as
+/// [`phf_shared::hash`] does for types implementing `PhfHash`. The runtime
+/// lookup must use the same function.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts; see
+/// [`try_generate_hash_with_hash_fn`].
 pub fn generate_hash_with_hash_fn<T, F>(entries: &[T], hash_fn: F) -> HashState
 where
     F: Fn(&T, &HashKey) -> Hashes,
 {
-    let mut generator = Generator::new(entries.len());
+    try_generate_hash_with_hash_fn(entries, hash_fn)
+        .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash_with_hash_fn`, but returns an error instead of
+/// panicking if no PHF is found within [`MAX_ATTEMPTS`] attempts.
+pub fn try_generate_hash_with_hash_fn<T, F>(
+    entries: &[T],
+    hash_fn: F,
+) -> Result<HashState, GenError>
+where
+    F: Fn(&T, &HashKey) -> Hashes,
+{
+    try_generate(entries, hash_fn, FIXED_SEED)
+}
+
+/// Generates a PHF from the precomputed hashes of the entries under the hash
+/// key `key`, without hashing the entries again.
+///
+/// `hashes[i]` must be the hash of entry `i` under `key`, as computed by
+/// [`phf_shared::hash`] or whichever function the runtime lookup uses.
+///
+/// As no other hash keys can be tried, this makes a single attempt and
+/// returns [`GenError::Unsolvable`] if there is no PHF for `key`. In that
+/// case, hash the entries under a different key and try again.
+pub fn try_generate_hash_from_hashes(
+    hashes: &[Hashes],
+    key: HashKey,
+) -> Result<HashState, GenError> {
+    check_len(hashes.len())?;
+
+    let mut generator = Generator::new();
+    generator.resize(hashes.len(), DEFAULT_LAMBDA);
+    generator.reset();
+    generator.hashes.extend_from_slice(hashes);
+    if generator.try_generate_hash() {
+        Ok(generator.state(key))
+    } else {
+        generator.check_duplicate_hashes()?;
+        Err(GenError::Unsolvable { attempts: 1 })
+    }
+}
+
+/// Like `generate_hash`, but tries several hash keys concurrently, and hashes
+/// the entries of each attempt on multiple threads.
+///
+/// The generator assigns buckets and displacements serially for a hash key,
+/// so the parallelism is across hash keys, along with the hashing of the
+/// entries, which dominates the time taken for large inputs. Keys are still
+/// drawn from the same sequence and the first one in that sequence which
+/// succeeds is used, so the result is identical to `generate_hash` regardless
+/// of the number of threads.
+///
+/// Requires the `parallel` feature.
+///
+/// # Panics
+///
+/// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts; see
+/// [`try_generate_hash_parallel`].
+#[cfg(feature = "parallel")]
+pub fn generate_hash_parallel<H: PhfHash + Sync>(entries: &[H]) -> HashState {
+    try_generate_hash_parallel(entries).unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+}
+
+/// Like `generate_hash_parallel`, but returns an error instead of panicking
+/// if no PHF is found within [`MAX_ATTEMPTS`] attempts.
+///
+/// Requires the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn try_generate_hash_parallel<H: PhfHash + Sync>(entries: &[H]) -> Result<HashState, GenError> {
+    use rayon::prelude::*;
+
+    check_len(entries.len())?;
+
     let mut rng = Rng::with_seed(FIXED_SEED);
+    let keys: Vec<HashKey> = std::iter::repeat_with(|| rng.next_u64())
+        .take(MAX_ATTEMPTS)
+        .collect();
+
+    keys.par_iter()
+        .map_init(
+            || {
+                let mut generator = Generator::new();
+                generator.resize(entries.len(), DEFAULT_LAMBDA);
+                generator
+            },
+            |generator, &key| {
+                generator.reset();
+                hash_entries(
+                    entries,
+                    |entry, key| phf_shared::hash(entry, key),
+                    key,
+                    &mut generator.hashes,
+                );
+
+                if generator.try_generate_hash() {
+                    Some(Ok(generator.state(key)))
+                } else {
+                    generator.check_duplicate_hashes().err().map(Err)
+                }
+            },
+        )
+        .find_map_first(|result| result)
+        .unwrap_or(Err(GenError::Unsolvable {
+            attempts: MAX_ATTEMPTS,
+        }))
+}
+
+/// Checks that `state` is a perfect hash for `entries`.
+///
+/// Returns `true` if every entry hashes to a distinct slot which maps back to
+/// that entry's index.
+pub fn verify<H: PhfHash>(entries: &[H], state: &HashState) -> bool {
+    if state.map.len() != entries.len() {
+        return false;
+    }
+    if entries.is_empty() {
+        return true;
+    }
+    if state.disps.is_empty() {
+        return false;
+    }
+
+    entries.iter().enumerate().all(|(i, entry)| {
+        let hashes = phf_shared::hash(entry, &state.key);
+        let idx = phf_shared::get_index(&hashes, &state.disps, entries.len()) as usize;
+        state.map[idx] == i
+    })
+}
+
+// Slot and bucket indices are computed with `u32` arithmetic at runtime.
+fn check_len(len: usize) -> Result<(), GenError> {
+    if len > u32::MAX as usize {
+        Err(GenError::TooManyEntries { len })
+    } else {
+        Ok(())
+    }
+}
+
+fn try_generate<T, F>(entries: &[T], hash_fn: F, seed: u64) -> Result<HashState, GenError>
+where
+    F: Fn(&T, &HashKey) -> Hashes,
+{
+    try_generate_with_stats(
+        &mut Generator::new(),
+        entries,
+        hash_fn,
+        seed,
+        &GenConfig::default(),
+    )
+    .map(|(state, _)| state)
+}
+
+fn try_generate_with_stats<T, F>(
+    generator: &mut Generator,
+    entries: &[T],
+    hash_fn: F,
+    seed: u64,
+    config: &GenConfig,
+) -> Result<(HashState, GenStats), GenError>
+where
+    F: Fn(&T, &HashKey) -> Hashes,
+{
+    try_generate_with_rng(
+        generator,
+        entries,
+        hash_fn,
+        Rng::with_seed(seed),
+        config,
+        &mut |_| {},
+    )
+}
 
-    iter::repeat_with(|| rng.u64(..))
-        .find(|key| {
-            let hashes = entries.iter().map(|entry| hash_fn(entry, key));
-            generator.reset(hashes);
+fn try_generate_with_rng<T, F, R>(
+    generator: &mut Generator,
+    entries: &[T],
+    hash_fn: F,
+    rng: R,
+    config: &GenConfig,
+    observer: &mut dyn FnMut(GenEvent<'_>),
+) -> Result<(HashState, GenStats), GenError>
+where
+    F: Fn(&T, &HashKey) -> Hashes,
+    R: RngLike,
+{
+    try_generate_attempts(
+        generator,
+        entries.len(),
+        |key, hashes| hashes.extend(entries.iter().map(|entry| hash_fn(entry, &key))),
+        rng,
+        config,
+        observer,
+    )
+}
 
-            generator.try_generate_hash()
-        })
-        .map(|key| HashState {
+// Fills the empty `hashes` with the hashes of all `entries` under `key` on the
+// rayon thread pool.
+#[cfg(feature = "parallel")]
+fn hash_entries<T, F>(entries: &[T], hash_fn: F, key: HashKey, hashes: &mut Vec<Hashes>)
+where
+    T: Sync,
+    F: Fn(&T, &HashKey) -> Hashes + Sync,
+{
+    use rayon::prelude::*;
+
+    entries
+        .par_iter()
+        .map(|entry| hash_fn(entry, &key))
+        .collect_into_vec(hashes)
+}
+
+// Tries hash keys drawn from `rng` in turn. `hash_all` fills the empty vector
+// it is given with the hashes of all `len` entries under a key.
+fn try_generate_attempts<H, R>(
+    generator: &mut Generator,
+    len: usize,
+    mut hash_all: H,
+    mut rng: R,
+    config: &GenConfig,
+    observer: &mut dyn FnMut(GenEvent<'_>),
+) -> Result<(HashState, GenStats), GenError>
+where
+    H: FnMut(HashKey, &mut Vec<Hashes>),
+    R: RngLike,
+{
+    assert!(config.lambda > 0, "lambda must be non-zero");
+    check_len(len)?;
+
+    generator.resize(len, config.lambda);
+
+    for attempt in 0..config.max_attempts {
+        let key = rng.next_u64();
+        generator.reset();
+        hash_all(key, &mut generator.hashes);
+
+        if generator.try_generate_hash() {
+            let state = generator.state(key);
+            let stats = GenStats::new(&state, attempt + 1, &generator.buckets);
+            observer(GenEvent::Solved(&stats));
+            return Ok((state, stats));
+        }
+        generator.check_duplicate_hashes()?;
+        observer(GenEvent::AttemptFailed {
+            attempt: attempt + 1,
             key,
-            disps: generator.disps,
-            map: generator.map.into_iter().map(|i| i.unwrap()).collect(),
-        })
-        .expect("failed to solve PHF")
+        });
+    }
+    Err(GenError::Unsolvable {
+        attempts: config.max_attempts,
+    })
 }
 
 struct Bucket {
@@ -49,51 +693,97 @@ struct Bucket {
     keys: Vec<usize>,
 }
 
-struct Generator {
+/// A PHF generator which reuses its scratch buffers across runs.
+///
+/// Generating many tables with a single `Generator` avoids reallocating the
+/// buffers used while searching for displacements for every table.
+#[derive(Default)]
+pub struct Generator {
     hashes: Vec<Hashes>,
     buckets: Vec<Bucket>,
     disps: Vec<(u32, u32)>,
     map: Vec<Option<usize>>,
     try_map: Vec<u64>,
+    // The indices of two entries in one bucket which share `f1` and `f2`, if
+    // the last attempt failed because of them.
+    collision: Option<(usize, usize)>,
 }
 
 impl Generator {
-    fn new(table_len: usize) -> Self {
-        let hashes = Vec::with_capacity(table_len);
-
-        let buckets_len = (table_len + DEFAULT_LAMBDA - 1) / DEFAULT_LAMBDA;
-        let buckets: Vec<_> = (0..buckets_len)
-            .map(|i| Bucket {
-                idx: i,
-                keys: vec![],
-            })
-            .collect();
-        let disps = vec![(0u32, 0u32); buckets_len];
-
-        let map = vec![None; table_len];
-        let try_map = vec![0u64; table_len];
-
-        Self {
-            hashes,
-            buckets,
-            disps,
-            map,
-            try_map,
-        }
-    }
-
-    fn reset<I>(&mut self, hashes: I)
-    where
-        I: Iterator<Item = Hashes>,
-    {
-        self.buckets.iter_mut().for_each(|b| b.keys.clear());
-        self.buckets.sort_by_key(|b| b.idx);
+    /// Creates a new generator with empty buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generates a PHF for `entries`, like [`generate_hash`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no PHF is found within [`MAX_ATTEMPTS`] attempts.
+    pub fn generate<H: PhfHash>(&mut self, entries: &[H]) -> HashState {
+        self.try_generate(entries)
+            .unwrap_or_else(|e| panic!("failed to solve PHF: {}", e))
+    }
+
+    /// Like `generate`, but returns an error instead of panicking if no PHF
+    /// is found within [`MAX_ATTEMPTS`] attempts.
+    pub fn try_generate<H: PhfHash>(&mut self, entries: &[H]) -> Result<HashState, GenError> {
+        try_generate_with_stats(
+            self,
+            entries,
+            phf_shared::hash,
+            FIXED_SEED,
+            &GenConfig::default(),
+        )
+        .map(|(state, _)| state)
+    }
+
+    fn state(&self, key: HashKey) -> HashState {
+        HashState {
+            key,
+            disps: self.disps.clone(),
+            map: self.map.iter().map(|i| i.unwrap()).collect(),
+        }
+    }
+
+    fn resize(&mut self, table_len: usize, lambda: usize) {
+        let buckets_len = (table_len + lambda - 1) / lambda;
+        self.buckets.resize_with(buckets_len, || Bucket {
+            idx: 0,
+            keys: vec![],
+        });
+        self.disps.resize(buckets_len, (0, 0));
+        self.map.resize(table_len, None);
+        self.try_map.resize(table_len, 0);
+    }
+
+    fn reset(&mut self) {
+        for (i, bucket) in self.buckets.iter_mut().enumerate() {
+            bucket.idx = i;
+            bucket.keys.clear();
+        }
         self.disps.iter_mut().for_each(|d| *d = (0, 0));
         self.map.iter_mut().for_each(|m| *m = None);
         self.try_map.iter_mut().for_each(|m| *m = 0);
 
         self.hashes.clear();
-        self.hashes.extend(hashes);
+        self.collision = None;
+    }
+
+    // Entries whose hashes are identical collide under every hash key, so the
+    // search fails at once rather than trying the remaining keys.
+    fn check_duplicate_hashes(&self) -> Result<(), GenError> {
+        match self.collision {
+            Some((first_index, index)) => {
+                let (a, b) = (&self.hashes[first_index], &self.hashes[index]);
+                if a.g == b.g && a.f1 == b.f1 && a.f2 == b.f2 {
+                    Err(GenError::DuplicateHashes { index, first_index })
+                } else {
+                    Ok(())
+                }
+            }
+            None => Ok(()),
+        }
     }
 
     fn try_generate_hash(&mut self) -> bool {
@@ -108,6 +798,30 @@ impl Generator {
 
         let table_len = self.hashes.len();
 
+        // With at most one entry, the zeroed displacements already place it
+        // in the only slot.
+        if table_len <= 1 {
+            if table_len == 1 {
+                self.map[0] = Some(0);
+            }
+            return true;
+        }
+
+        // Entries in one bucket which share `f1` and `f2` are displaced to the
+        // same slot by every `(d1, d2)`, so fail before searching all of them.
+        for bucket in &self.buckets {
+            for (i, &index) in bucket.keys.iter().enumerate() {
+                let hash = &self.hashes[index];
+                for &first_index in &bucket.keys[..i] {
+                    let first = &self.hashes[first_index];
+                    if first.f1 == hash.f1 && first.f2 == hash.f2 {
+                        self.collision = Some((first_index, index));
+                        return false;
+                    }
+                }
+            }
+        }
+
         // store whether an element from the bucket being placed is
         // located at a certain position, to allow for efficient overlap
         // checks. It works by storing the generation in each cell and
@@ -154,3 +868,293 @@ impl Generator {
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_generate_hash() {
+        let state = super::try_generate_hash(&["a", "b", "c"]).unwrap();
+        assert_eq!(3, state.map.len());
+    }
+
+    #[test]
+    fn degenerate_sizes() {
+        let state = generate_hash::<u32>(&[]);
+        assert!(state.disps.is_empty());
+        assert!(state.map.is_empty());
+        assert!(super::verify::<u32>(&[], &state));
+
+        let state = generate_hash(&["a"]);
+        assert_eq!(vec![(0, 0)], state.disps);
+        assert_eq!(vec![0], state.map);
+        assert!(super::verify(&["a"], &state));
+
+        let state = generate_hash(&["a", "b"]);
+        assert_eq!(1, state.disps.len());
+        let mut slots = state.map.clone();
+        slots.sort_unstable();
+        assert_eq!(vec![0, 1], slots);
+        assert!(super::verify(&["a", "b"], &state));
+    }
+
+    #[test]
+    fn accessors() {
+        let state = generate_hash(&["a", "b", "c"]);
+        assert_eq!(&state.disps[..], state.disps());
+        assert_eq!(&state.map[..], state.slot_map());
+        let mut slots = state.slot_map().to_vec();
+        slots.sort_unstable();
+        assert_eq!(vec![0, 1, 2], slots);
+    }
+
+    #[test]
+    fn generate_hash_with_stats() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let (state, stats) = super::generate_hash_with_stats(&entries);
+        assert_eq!(generate_hash(&entries).key, state.key);
+        assert_eq!(state.key, stats.key);
+        assert!(stats.attempts >= 1);
+        assert_eq!(100, stats.slots);
+        assert_eq!(20, stats.buckets);
+        assert_eq!(1.0, stats.load_factor);
+        assert_eq!(state.max_disp(), stats.max_disp);
+        assert!(state
+            .disps
+            .iter()
+            .all(|&(d1, d2)| d1.max(d2) <= stats.max_disp));
+        assert!(state
+            .disps
+            .iter()
+            .any(|&(d1, d2)| d1.max(d2) == stats.max_disp));
+        assert_eq!(20, stats.bucket_sizes.iter().sum::<usize>());
+        assert_eq!(
+            100,
+            stats
+                .bucket_sizes
+                .iter()
+                .enumerate()
+                .map(|(len, count)| len * count)
+                .sum::<usize>()
+        );
+        assert_ne!(0, stats.bucket_sizes[stats.max_bucket_len()]);
+        assert_eq!(5.0, stats.mean_bucket_len());
+    }
+
+    #[test]
+    fn generate_hash_with_rng() {
+        struct Counter(u64);
+
+        impl RngLike for Counter {
+            fn next_u64(&mut self) -> u64 {
+                self.0 += 1;
+                self.0
+            }
+        }
+
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let state = super::generate_hash_with_rng(&entries, Counter(0));
+        assert!(super::verify(&entries, &state));
+        assert_eq!(
+            state.key,
+            super::generate_hash_with_rng(&entries, &mut Counter(0)).key
+        );
+        assert_eq!(
+            generate_hash(&entries).key,
+            super::generate_hash_with_rng(&entries, Rng::with_seed(FIXED_SEED)).key
+        );
+
+        let mut rng = Counter(0);
+        assert_eq!(
+            Some(GenError::DuplicateHashes {
+                index: 1,
+                first_index: 0
+            }),
+            super::try_generate_hash_with_rng(&["a", "a"], &mut rng).err()
+        );
+        assert_eq!(1, rng.0);
+    }
+
+    #[test]
+    fn generate_hash_with_observer() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let mut events = vec![];
+        let state = super::generate_hash_with_observer(&entries, |event| match event {
+            GenEvent::AttemptFailed { attempt, .. } => events.push(attempt),
+            GenEvent::Solved(stats) => events.push(stats.attempts),
+        });
+        let (expected, stats) = super::generate_hash_with_stats(&entries);
+        assert_eq!(expected.key, state.key);
+        assert_eq!((1..=stats.attempts).collect::<Vec<_>>(), events);
+
+        // duplicates fail before any attempt is reported
+        let mut failures = 0;
+        let result = super::try_generate_hash_with_observer(&["a", "a"], |_| failures += 1);
+        assert!(matches!(result, Err(GenError::DuplicateHashes { .. })));
+        assert_eq!(0, failures);
+    }
+
+    #[test]
+    fn generate_hash_min_size() {
+        let entries = (0..1000u32).collect::<Vec<_>>();
+        let state = super::generate_hash_min_size(&entries);
+        assert!(super::verify(&entries, &state));
+        assert!(state.disps.len() <= generate_hash(&entries).disps.len());
+    }
+
+    #[test]
+    fn try_generate_hash_min_size() {
+        let entries = (0..10_000u32).collect::<Vec<_>>();
+        let state = super::try_generate_hash_min_size(&entries).unwrap();
+        assert_eq!(super::generate_hash_min_size(&entries).key, state.key);
+        assert_eq!(10_000 / 8, state.disps.len());
+
+        assert_eq!(
+            Some(GenError::DuplicateHashes {
+                index: 2,
+                first_index: 0
+            }),
+            super::try_generate_hash_min_size(&["a", "b", "a"]).err()
+        );
+    }
+
+    #[test]
+    fn generate_hash_with_config() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        assert_eq!(
+            generate_hash(&entries).disps,
+            super::generate_hash_with_config(&entries, &GenConfig::default()).disps
+        );
+
+        let state = super::generate_hash_with_config(
+            &entries,
+            &GenConfig {
+                lambda: 2,
+                ..GenConfig::default()
+            },
+        );
+        assert_eq!(50, state.disps.len());
+        let mut map = state.map.clone();
+        map.sort_unstable();
+        assert_eq!((0..100).collect::<Vec<_>>(), map);
+    }
+
+    #[test]
+    fn try_generate_hash_from_hashes() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let expected = generate_hash(&entries);
+        let hashes = entries
+            .iter()
+            .map(|entry| phf_shared::hash(entry, &expected.key))
+            .collect::<Vec<_>>();
+        let state = super::try_generate_hash_from_hashes(&hashes, expected.key).unwrap();
+        assert_eq!(expected.key, state.key);
+        assert_eq!(expected.disps, state.disps);
+        assert_eq!(expected.map, state.map);
+
+        // every entry lands in the same bucket with the same hashes
+        let hashes = vec![phf_shared::hash(&0u32, &0); 2];
+        assert_eq!(
+            Some(GenError::DuplicateHashes {
+                index: 1,
+                first_index: 0
+            }),
+            super::try_generate_hash_from_hashes(&hashes, 0).err()
+        );
+    }
+
+    #[test]
+    fn reuse_generator() {
+        let mut generator = Generator::new();
+        for len in [100u32, 3, 0, 50, 100] {
+            let entries = (0..len).collect::<Vec<_>>();
+            let expected = generate_hash(&entries);
+            let state = generator.generate(&entries);
+            assert_eq!(expected.key, state.key);
+            assert_eq!(expected.disps, state.disps);
+            assert_eq!(expected.map, state.map);
+        }
+        assert!(generator.try_generate(&["a", "a"]).is_err());
+    }
+
+    #[test]
+    fn generate_hash_bounded() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let state = super::generate_hash_bounded(&entries, MAX_ATTEMPTS).unwrap();
+        assert_eq!(generate_hash(&entries).key, state.key);
+
+        assert!(super::generate_hash_bounded(&entries, 0).is_none());
+        assert!(super::generate_hash_bounded(&["a", "a"], 5).is_none());
+    }
+
+    #[test]
+    fn verify() {
+        let entries = (0..100u32).collect::<Vec<_>>();
+        let mut state = generate_hash(&entries);
+        assert!(super::verify(&entries, &state));
+        assert!(!super::verify(&entries[..99], &state));
+
+        state.map.swap(0, 1);
+        assert!(!super::verify(&entries, &state));
+
+        assert!(super::verify::<u32>(&[], &generate_hash::<u32>(&[])));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn too_many_entries() {
+        let len = u32::MAX as usize + 1;
+        let hash_fn = |_: &(), key: &HashKey| phf_shared::hash(&0u32, key);
+        assert_eq!(
+            Err(GenError::TooManyEntries { len }),
+            try_generate_hash_with_hash_fn(&vec![(); len], hash_fn).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn try_generate_hash_unsolvable() {
+        let hash_fn = |_: &u32, _: &HashKey| phf_shared::hash(&0u32, &0);
+        assert_eq!(
+            Err(GenError::DuplicateHashes {
+                index: 1,
+                first_index: 0
+            }),
+            try_generate_hash_with_hash_fn(&[1, 2, 3], hash_fn).map(|_| ())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn generate_hash_parallel() {
+        let entries = (0..1000u32).collect::<Vec<_>>();
+        let expected = generate_hash(&entries);
+        let state = super::generate_hash_parallel(&entries);
+        assert_eq!(expected.key, state.key);
+        assert_eq!(expected.disps, state.disps);
+        assert_eq!(expected.map, state.map);
+
+        assert!(super::try_generate_hash_parallel(&["a", "a"]).is_err());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "failed to solve PHF: entries at indices 0 and 2 have identical hashes"
+    )]
+    fn generate_hash_unsolvable() {
+        generate_hash(&["a", "b", "a"]);
+    }
+
+    #[test]
+    fn duplicate_hashes() {
+        let mut entries = (0..5000u32).collect::<Vec<_>>();
+        entries.push(1234);
+        assert_eq!(
+            Some(GenError::DuplicateHashes {
+                index: 5000,
+                first_index: 1234
+            }),
+            super::try_generate_hash(&entries).err()
+        );
+    }
+}
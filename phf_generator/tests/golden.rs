@@ -0,0 +1,25 @@
+//! Pins the output of `generate_hash` for a fixed input.
+//!
+//! If this test fails, the generated layout has changed, and any previously
+//! generated source will be regenerated differently. Update the expected
+//! values only as part of an intentional, documented change.
+
+use phf_generator::generate_hash;
+
+#[test]
+fn golden() {
+    let entries = [
+        "apple",
+        "banana",
+        "cherry",
+        "date",
+        "elderberry",
+        "fig",
+        "grape",
+    ];
+    let state = generate_hash(&entries);
+
+    assert_eq!(0x25543c529f98ddd2, state.key);
+    assert_eq!(vec![(5, 1), (3, 0)], state.disps);
+    assert_eq!(vec![2, 0, 6, 3, 1, 4, 5], state.map);
+}
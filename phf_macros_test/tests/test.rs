@@ -114,6 +114,261 @@ mod map {
         assert_eq!(Some(&0), MAP.get(&*"a".to_string()));
     }
 
+    #[test]
+    fn test_double_ended_exact_size() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+            "baz" => 12,
+        );
+        let forward = MAP.entries().collect::<Vec<_>>();
+        let mut backward = MAP.entries().rev().collect::<Vec<_>>();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        let mut entries = MAP.entries();
+        assert_eq!(3, entries.len());
+        assert_eq!(forward[0], entries.next().unwrap());
+        assert_eq!(2, entries.len());
+        assert_eq!(forward[2], entries.next_back().unwrap());
+        assert_eq!(1, entries.len());
+        assert_eq!(forward[1], entries.next().unwrap());
+        assert_eq!(0, entries.len());
+        assert_eq!(None, entries.next_back());
+
+        let mut keys = MAP.keys();
+        assert_eq!(3, keys.len());
+        assert_eq!(Some(forward[2].0), keys.next_back());
+        assert_eq!(2, keys.len());
+        assert_eq!(
+            forward.iter().map(|e| e.0).take(2).collect::<Vec<_>>(),
+            keys.collect::<Vec<_>>()
+        );
+
+        let mut values = MAP.values();
+        assert_eq!(3, values.len());
+        assert_eq!(Some(forward[0].1), values.next());
+        assert_eq!(Some(forward[2].1), values.next_back());
+        assert_eq!(1, values.len());
+    }
+
+    #[test]
+    fn test_owned_entries() {
+        let map: phf::Map<u32, u32> = phf_map!(
+            1u32 => 10,
+            2u32 => 20,
+            3u32 => 30,
+        );
+        let expected = map.entries().map(|(&k, &v)| (k, v)).collect::<Vec<_>>();
+
+        let mut by_ref = vec![];
+        for (&k, &v) in &map {
+            by_ref.push((k, v));
+        }
+        assert_eq!(expected, by_ref);
+
+        let entries = map.owned_entries();
+        assert_eq!(3, entries.len());
+        let mut reversed = entries.clone().rev().collect::<Vec<_>>();
+        reversed.reverse();
+        assert_eq!(expected, reversed);
+        assert_eq!(expected, entries.collect::<Vec<(u32, u32)>>());
+
+        static STATIC_MAP: phf::Map<&'static str, u32> = phf_map!("a" => 1);
+        let pairs: Vec<(&&str, &u32)> = STATIC_MAP.into_iter().collect();
+        assert_eq!(vec![(&"a", &1)], pairs);
+    }
+
+    #[test]
+    fn test_iterator_traits() {
+        fn assert_traits<I>(iter: I) -> I
+        where
+            I: Clone + DoubleEndedIterator + ExactSizeIterator + std::iter::FusedIterator,
+        {
+            iter
+        }
+
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+            "baz" => 12,
+        );
+        let keys = assert_traits(MAP.keys());
+        let values = assert_traits(MAP.values());
+        assert_traits(MAP.entries());
+
+        let pairs = keys.clone().zip(values.clone()).rev().collect::<Vec<_>>();
+        let mut expected = MAP.entries().collect::<Vec<_>>();
+        expected.reverse();
+        assert_eq!(expected, pairs);
+        assert_eq!(3, keys.len());
+        assert_eq!(3, values.len());
+    }
+
+    #[test]
+    fn test_get_or() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+        );
+        assert_eq!(10, MAP.get_or("foo", 0));
+        assert_eq!(0, MAP.get_or("bar", 0));
+        assert_eq!(&10, MAP.get_ref_or("foo", &-1));
+        assert_eq!(&-1, MAP.get_ref_or("bar", &-1));
+    }
+
+    #[test]
+    fn test_get_internal_index() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+            "baz" => 12,
+        );
+        let mut seen = [false; 3];
+        for (key, value) in &MAP {
+            let index = MAP.get_internal_index(key).unwrap();
+            assert_eq!(value, MAP.entries().nth(index).unwrap().1);
+            seen[index] = true;
+        }
+        assert_eq!([true; 3], seen);
+        assert_eq!(None, MAP.get_internal_index("qux"));
+        assert_eq!(
+            None,
+            phf::Map::<&str, isize>::new().get_internal_index("foo")
+        );
+    }
+
+    #[test]
+    fn test_contains_any() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        assert!(MAP.contains_any(["baz", "bar"]));
+        assert!(!MAP.contains_any(["baz", "qux"]));
+        assert!(!MAP.contains_any(Vec::<&str>::new()));
+    }
+
+    #[test]
+    fn test_get_many() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        let values = MAP.get_many(["bar", "baz", "foo"]).collect::<Vec<_>>();
+        assert_eq!(vec![Some(&11), None, Some(&10)], values);
+        assert_eq!(3, MAP.get_many(["a", "b", "c"]).len());
+        assert_eq!(Some(Some(&10)), MAP.get_many(["bar", "foo"]).next_back());
+    }
+
+    #[test]
+    fn test_entries_named_type() {
+        struct Cursor {
+            entries: phf::map::Entries<'static, &'static str, isize>,
+        }
+
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        let mut cursor = Cursor {
+            entries: MAP.entries(),
+        };
+        let first = cursor.entries.next().unwrap();
+        assert_eq!(Some(first.1), MAP.get(first.0));
+        assert_eq!(1, cursor.entries.len());
+    }
+
+    #[test]
+    fn test_get_const() {
+        static MAP: phf::Map<u32, u32> = phf_map!(
+            1u32 => 10,
+            2u32 => 20,
+            0xdead_beefu32 => 30,
+        );
+        const TWENTY: Option<&u32> = MAP.get_const(2);
+        const MISSING: Option<&u32> = MAP.get_const(3);
+        assert_eq!(Some(&20), TWENTY);
+        assert_eq!(None, MISSING);
+        assert_eq!(Some(&30), MAP.get_const(0xdead_beef));
+
+        static SIGNED: phf::Map<i64, u32> = phf_map!(
+            -1i64 => 1,
+            -42i64 => 2,
+            9223372036854775807i64 => 3,
+        );
+        for key in [-1, -42, i64::MAX, 0] {
+            assert_eq!(SIGNED.get(&key), SIGNED.get_const(key));
+        }
+
+        static CHARS: phf::Map<char, u32> = phf_map!('a' => 1, 'é' => 2);
+        assert_eq!(Some(&2), CHARS.get_const('é'));
+        assert_eq!(None, CHARS.get_const('b'));
+
+        static EMPTY: phf::Map<u8, u32> = phf_map!();
+        assert_eq!(None, EMPTY.get_const(0));
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        static SAME: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        static OTHER: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 12,
+        );
+
+        fn hash<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert_eq!(MAP, SAME);
+        assert_eq!(hash(&MAP), hash(&SAME));
+        assert_ne!(hash(&MAP), hash(&OTHER));
+    }
+
+    #[test]
+    fn test_get_entry() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        let key = "foo".to_string();
+        let (interned, value) = MAP.get_entry(&*key).unwrap();
+        assert_eq!(("foo", 10), (*interned, *value));
+        assert_eq!(interned.as_ptr(), MAP.get_key("foo").unwrap().as_ptr());
+        assert_eq!(None, MAP.get_entry("baz"));
+    }
+
+    #[test]
+    fn test_intern() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        let key = "foo".to_string();
+        let interned: &'static str = MAP.intern(&*key).unwrap();
+        assert_eq!("foo", interned);
+        assert_ne!(key.as_ptr(), interned.as_ptr());
+        assert_eq!(interned.as_ptr(), MAP.intern("foo").unwrap().as_ptr());
+        assert_eq!(None, MAP.intern("baz"));
+
+        static SET: phf::Set<&'static str> = phf::phf_set!("foo", "bar");
+        let interned: &'static str = SET.intern(&*key).unwrap();
+        assert_eq!(interned.as_ptr(), SET.intern("foo").unwrap().as_ptr());
+        assert_eq!(None, SET.intern("baz"));
+    }
+
     #[test]
     fn test_index_ok() {
         static MAP: phf::Map<&'static str, isize> = phf_map!(
@@ -123,7 +378,7 @@ mod map {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "no entry found for key")]
     fn test_index_fail() {
         static MAP: phf::Map<&'static str, isize> = phf_map!(
             "a" => 0,
@@ -368,6 +623,16 @@ mod map {
         assert_eq!(Some(&30), MAP2.get("xyz"));
         assert_eq!(None, MAP2.get("unknown"));
     }
+
+    #[test]
+    fn test_serialize() {
+        static MAP: phf::Map<&'static str, isize> = phf_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        let json = serde_json::to_value(&MAP).unwrap();
+        assert_eq!(serde_json::json!({ "foo": 10, "bar": 11 }), json);
+    }
 }
 
 mod set {
@@ -488,6 +753,134 @@ mod set {
         assert!(!SET2.contains("unknown"));
         assert_eq!(6, SET2.len());
     }
+
+    static ONE_TO_THREE: phf::Set<u32> = phf_set!(1u32, 2u32, 3u32);
+    static TWO_TO_FOUR: phf::Set<u32> = phf_set!(2u32, 3u32, 4u32);
+
+    #[test]
+    fn test_difference() {
+        let difference = ONE_TO_THREE
+            .difference(&TWO_TO_FOUR)
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([1]), difference);
+
+        let difference = TWO_TO_FOUR
+            .difference(&ONE_TO_THREE)
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([4]), difference);
+
+        assert_eq!(0, ONE_TO_THREE.difference(&ONE_TO_THREE).count());
+        assert_eq!(
+            "[1]",
+            format!("{:?}", ONE_TO_THREE.difference(&TWO_TO_FOUR))
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let intersection = ONE_TO_THREE
+            .intersection(&TWO_TO_FOUR)
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([2, 3]), intersection);
+        assert_eq!(3, ONE_TO_THREE.intersection(&ONE_TO_THREE).count());
+    }
+
+    #[test]
+    fn test_union() {
+        let union = ONE_TO_THREE
+            .union(&TWO_TO_FOUR)
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(4, union.len());
+        assert_eq!(HashSet::from([1, 2, 3, 4]), union.into_iter().collect());
+        assert_eq!(3, ONE_TO_THREE.union(&ONE_TO_THREE).count());
+    }
+
+    #[test]
+    fn test_hash() {
+        #[derive(Hash, PartialEq, Eq)]
+        struct Wrapper(&'static phf::Set<u32>);
+
+        let set = [
+            Wrapper(&ONE_TO_THREE),
+            Wrapper(&ONE_TO_THREE),
+            Wrapper(&TWO_TO_FOUR),
+        ]
+        .into_iter()
+        .collect::<HashSet<_>>();
+        assert_eq!(2, set.len());
+    }
+
+    #[test]
+    fn test_ord() {
+        use std::cmp::Ordering;
+        use std::collections::BTreeMap;
+
+        static ONE_TO_THREE_AGAIN: phf::Set<u32> = phf_set!(1u32, 2u32, 3u32);
+
+        assert_eq!(Ordering::Equal, ONE_TO_THREE.cmp(&ONE_TO_THREE_AGAIN));
+        assert_eq!(
+            ONE_TO_THREE.cmp(&TWO_TO_FOUR),
+            ONE_TO_THREE.iter().cmp(TWO_TO_FOUR.iter())
+        );
+        assert_eq!(
+            Some(TWO_TO_FOUR.cmp(&ONE_TO_THREE)),
+            TWO_TO_FOUR.partial_cmp(&ONE_TO_THREE)
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert(&ONE_TO_THREE, "a");
+        map.insert(&TWO_TO_FOUR, "b");
+        map.insert(&ONE_TO_THREE_AGAIN, "c");
+        assert_eq!(2, map.len());
+        assert_eq!(Some(&"c"), map.get(&ONE_TO_THREE));
+    }
+
+    #[test]
+    fn test_contains_const() {
+        const CONTAINS: [bool; 2] = [
+            ONE_TO_THREE.contains_const(2),
+            ONE_TO_THREE.contains_const(4),
+        ];
+        assert_eq!([true, false], CONTAINS);
+    }
+
+    #[test]
+    fn test_contains_any() {
+        assert!(ONE_TO_THREE.contains_any(&[4, 3]));
+        assert!(!ONE_TO_THREE.contains_any(&[0, 4]));
+    }
+
+    #[test]
+    fn test_contains_iter() {
+        let found = ONE_TO_THREE
+            .contains_iter(&[0, 1, 3, 4])
+            .collect::<Vec<_>>();
+        assert_eq!(vec![false, true, true, false], found);
+        assert_eq!(0, ONE_TO_THREE.contains_iter(&[]).count());
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let symmetric_difference = ONE_TO_THREE
+            .symmetric_difference(&TWO_TO_FOUR)
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(HashSet::from([1, 4]), symmetric_difference);
+        assert_eq!(0, ONE_TO_THREE.symmetric_difference(&ONE_TO_THREE).count());
+    }
+
+    #[test]
+    fn test_serialize() {
+        static SET: phf::Set<&'static str> = phf_set!("foo", "bar");
+        let mut values: Vec<String> =
+            serde_json::from_str(&serde_json::to_string(&SET).unwrap()).unwrap();
+        values.sort();
+        assert_eq!(vec!["bar", "foo"], values);
+    }
 }
 
 mod ordered_map {
@@ -542,6 +935,24 @@ mod ordered_map {
         assert_eq!(None, MAP.index(2));
     }
 
+    #[test]
+    fn test_index_out_of_bounds() {
+        static MAP: phf::OrderedMap<&'static str, isize> = phf_ordered_map!(
+            "foo" => 5,
+            "bar" => 6,
+            "baz" => 7,
+        );
+        assert_eq!(Some((&"baz", &7)), MAP.index(MAP.len() - 1));
+        assert_eq!(None, MAP.index(MAP.len()));
+        assert_eq!(None, MAP.index(usize::MAX));
+
+        static EMPTY: phf::OrderedMap<u32, u32> = phf_ordered_map!();
+        assert_eq!(None, EMPTY.index(0));
+
+        let page = (2..4).map_while(|i| MAP.index(i)).collect::<Vec<_>>();
+        assert_eq!(vec![(&"baz", &7)], page);
+    }
+
     #[test]
     fn test_entries() {
         static MAP: phf::OrderedMap<&'static str, i32> = phf_ordered_map!(
@@ -584,7 +995,7 @@ mod ordered_map {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "no entry found for key")]
     fn test_index_fail() {
         static MAP: phf::OrderedMap<&'static str, isize> = phf_ordered_map!(
             "a" => 0,
@@ -672,6 +1083,18 @@ mod ordered_map {
         assert_eq!(None, MAP2.get("unknown"));
         assert_eq!(6, MAP2.len());
     }
+
+    #[test]
+    fn test_serialize() {
+        static MAP: phf::OrderedMap<&'static str, isize> = phf_ordered_map!(
+            "foo" => 10,
+            "bar" => 11,
+        );
+        assert_eq!(
+            r#"{"foo":10,"bar":11}"#,
+            serde_json::to_string(&MAP).unwrap()
+        );
+    }
 }
 
 mod ordered_set {
@@ -717,6 +1140,22 @@ mod ordered_set {
         assert_eq!(None, SET.get_index(&*"xyz".to_string()));
     }
 
+    #[test]
+    fn test_get_index_insertion_order() {
+        static SET: phf::OrderedSet<&'static str> = phf_ordered_set! {
+            "zero",
+            "one",
+            "two",
+            "three",
+            "four",
+        };
+        for (i, value) in SET.iter().enumerate() {
+            assert_eq!(Some(i), SET.get_index(*value));
+            assert_eq!(Some(value), SET.index(i));
+        }
+        assert_eq!(Some(3), SET.get_index("three"));
+    }
+
     #[test]
     fn test_index() {
         static MAP: phf::OrderedSet<&'static str> = phf_ordered_set!("foo", "bar",);
@@ -814,4 +1253,10 @@ mod ordered_set {
         assert!(!SET2.contains("unknown"));
         assert_eq!(6, SET2.len());
     }
+
+    #[test]
+    fn test_serialize() {
+        static SET: phf::OrderedSet<&'static str> = phf_ordered_set!("foo", "bar");
+        assert_eq!(r#"["foo","bar"]"#, serde_json::to_string(&SET).unwrap());
+    }
 }
@@ -10,9 +10,9 @@ extern crate std as core;
 
 use core::fmt;
 use core::hash::{Hash, Hasher};
-use core::num::Wrapping;
 use siphasher::sip128::{Hash128, Hasher128, SipHasher13};
 
+#[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
 pub struct Hashes {
     pub g: u32,
@@ -26,8 +26,8 @@ pub struct Hashes {
 pub type HashKey = u64;
 
 #[inline]
-pub fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
-    (Wrapping(d2) + Wrapping(f1) * Wrapping(d1) + Wrapping(f2)).0
+pub const fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
+    d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2)
 }
 
 /// `key` is from `phf_generator::HashState`.
@@ -48,13 +48,80 @@ pub fn hash<T: ?Sized + PhfHash>(x: &T, key: &HashKey) -> Hashes {
     }
 }
 
+/// Like `hash`, but usable in const contexts for integers of at most 8 bytes.
+///
+/// `value` is the integer zero-extended to a `u64` (so an `i8` of `-1` is
+/// `0xff`), and `len` is its size in bytes. The result is the same as `hash`
+/// for the corresponding integer type.
+pub const fn hash_int(value: u64, len: usize, key: &HashKey) -> Hashes {
+    macro_rules! compress {
+        ($v0:ident, $v1:ident, $v2:ident, $v3:ident) => {
+            $v0 = $v0.wrapping_add($v1);
+            $v1 = $v1.rotate_left(13);
+            $v1 ^= $v0;
+            $v0 = $v0.rotate_left(32);
+            $v2 = $v2.wrapping_add($v3);
+            $v3 = $v3.rotate_left(16);
+            $v3 ^= $v2;
+            $v0 = $v0.wrapping_add($v3);
+            $v3 = $v3.rotate_left(21);
+            $v3 ^= $v0;
+            $v2 = $v2.wrapping_add($v1);
+            $v1 = $v1.rotate_left(17);
+            $v1 ^= $v2;
+            $v2 = $v2.rotate_left(32);
+        };
+    }
+
+    assert!(len <= 8, "integers wider than 8 bytes are not supported");
+
+    // SipHash-1-3 with 128-bit output and keys `(0, key)`, as used by `hash`.
+    let (k0, k1) = (0u64, *key);
+    let mut v0 = k0 ^ 0x736f6d6570736575;
+    let mut v1 = k1 ^ 0x646f72616e646f83;
+    let mut v2 = k0 ^ 0x6c7967656e657261;
+    let mut v3 = k1 ^ 0x7465646279746573;
+
+    let tail = if len == 8 {
+        v3 ^= value;
+        compress!(v0, v1, v2, v3);
+        v0 ^= value;
+        0
+    } else {
+        value
+    };
+
+    let b = ((len as u64) << 56) | tail;
+    v3 ^= b;
+    compress!(v0, v1, v2, v3);
+    v0 ^= b;
+
+    v2 ^= 0xee;
+    compress!(v0, v1, v2, v3);
+    compress!(v0, v1, v2, v3);
+    compress!(v0, v1, v2, v3);
+    let lower = v0 ^ v1 ^ v2 ^ v3;
+
+    v1 ^= 0xdd;
+    compress!(v0, v1, v2, v3);
+    compress!(v0, v1, v2, v3);
+    compress!(v0, v1, v2, v3);
+    let upper = v0 ^ v1 ^ v2 ^ v3;
+
+    Hashes {
+        g: (lower >> 32) as u32,
+        f1: lower as u32,
+        f2: upper as u32,
+    }
+}
+
 /// Return an index into `phf_generator::HashState::map`.
 ///
 /// * `hash` is from `hash()` in this crate.
 /// * `disps` is from `phf_generator::HashState::disps`.
 /// * `len` is the length of `phf_generator::HashState::map`.
 #[inline]
-pub fn get_index(hashes: &Hashes, disps: &[(u32, u32)], len: usize) -> u32 {
+pub const fn get_index(hashes: &Hashes, disps: &[(u32, u32)], len: usize) -> u32 {
     let (d1, d2) = disps[(hashes.g % (disps.len() as u32)) as usize];
     displace(hashes.f1, hashes.f2, d1, d2) % (len as u32)
 }
@@ -80,11 +147,77 @@ pub trait PhfHash {
 }
 
 /// Trait for printing types with `const` constructors, used by `phf_codegen` and `phf_macros`.
+///
+/// # Floats
+///
+/// `f32` and `f64` are printed bit-exactly, except that every NaN is printed
+/// as `NAN` and so loses its payload. `check_fmt_const` rejects NaNs for that
+/// reason. Their `PhfHash` implementations hash the raw bits, so `0.0` and
+/// `-0.0` are different keys. Float keys must be canonicalized, for example by
+/// a newtype providing `Eq`, before use in a PHF data structure.
 pub trait FmtConst {
     /// Print a `const` expression representing this value.
     fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /// Returns an error if this value cannot be represented as a `const`
+    /// expression.
+    ///
+    /// This is what `phf_codegen` calls to validate keys before building, so
+    /// implementations which reject some values should override it rather
+    /// than print source which does not compile. The default implementation
+    /// runs `fmt_const` into a writer which discards its output, and returns
+    /// an error if it does.
+    fn check_fmt_const(&self) -> Result<(), FmtConstError> {
+        struct Discard;
+
+        impl fmt::Write for Discard {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        fmt::Write::write_fmt(&mut Discard, format_args!("{}", Const(self)))
+            .map_err(|_| FmtConstError)
+    }
+
+    /// Returns a `const` expression representing this value, or an error if
+    /// it cannot be represented as one.
+    ///
+    /// The default implementation returns an error if `check_fmt_const` or
+    /// `fmt_const` does.
+    #[cfg(feature = "std")]
+    fn try_fmt_const(&self) -> Result<String, FmtConstError> {
+        self.check_fmt_const()?;
+        let mut source = String::new();
+        fmt::Write::write_fmt(&mut source, format_args!("{}", Const(self)))
+            .map_err(|_| FmtConstError)?;
+        Ok(source)
+    }
+}
+
+/// Displays a value using its `FmtConst` implementation.
+struct Const<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: FmtConst + ?Sized> fmt::Display for Const<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_const(f)
+    }
+}
+
+/// An error returned by `FmtConst::check_fmt_const` for a value which cannot be
+/// represented as a `const` expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FmtConstError;
+
+impl fmt::Display for FmtConstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value cannot be represented as a const expression")
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for FmtConstError {}
+
 /// Identical to `std::borrow::Borrow` except omitting blanket impls to facilitate other
 /// borrowing patterns.
 ///
@@ -143,7 +276,6 @@ macro_rules! delegate_debug (
 );
 
 delegate_debug!(str);
-delegate_debug!(char);
 delegate_debug!(u8);
 delegate_debug!(i8);
 delegate_debug!(u16);
@@ -154,10 +286,84 @@ delegate_debug!(u64);
 delegate_debug!(i64);
 delegate_debug!(usize);
 delegate_debug!(isize);
-delegate_debug!(u128);
-delegate_debug!(i128);
 delegate_debug!(bool);
 
+/// Create an impl of `FmtConst` emitting an integer literal with an explicit type suffix.
+macro_rules! suffixed_literal (
+    ($ty:ident) => {
+        impl FmtConst for $ty {
+            fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}{}", self, stringify!($ty))
+            }
+        }
+    }
+);
+
+suffixed_literal!(u128);
+suffixed_literal!(i128);
+
+/// Create impls of `FmtConst` and `PhfHash` for a float type.
+///
+/// Finite values are written using their shortest round-tripping
+/// representation, so the constructed literal has exactly the same bits, and
+/// `-0.0` stays distinct from `0.0`. NaNs are all written as the type's `NAN`
+/// constant, so a NaN's payload is *not* preserved, and `check_fmt_const`
+/// returns an error for them instead. Hashing is over the raw
+/// bits, so values which compare equal such as `0.0` and `-0.0` hash
+/// differently, and keys must be canonicalized before being used with PHF
+/// data structures.
+///
+/// As floats are not `Eq`, maps can neither be built from nor looked up with
+/// them directly. Use a newtype which only holds canonical values, such as
+/// `0.0` in place of `-0.0` and no NaNs, implements `Eq`, and delegates
+/// `PhfHash` and `FmtConst` to these impls.
+macro_rules! float_impl (
+    ($ty:ident) => {
+        impl FmtConst for $ty {
+            fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.is_nan() {
+                    write!(f, "::core::primitive::{}::NAN", stringify!($ty))
+                } else if self.is_infinite() {
+                    let sign = if self.is_sign_negative() { "NEG_" } else { "" };
+                    write!(f, "::core::primitive::{}::{}INFINITY", stringify!($ty), sign)
+                } else {
+                    write!(f, "{:?}{}", self, stringify!($ty))
+                }
+            }
+
+            fn check_fmt_const(&self) -> Result<(), FmtConstError> {
+                if self.is_nan() {
+                    return Err(FmtConstError);
+                }
+                Ok(())
+            }
+        }
+
+        impl PhfHash for $ty {
+            #[inline]
+            fn phf_hash<H: Hasher>(&self, state: &mut H) {
+                self.to_bits().phf_hash(state)
+            }
+        }
+    }
+);
+
+float_impl!(f32);
+float_impl!(f64);
+
+impl FmtConst for char {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `Debug` already escapes quotes, backslashes and non-printable
+        // characters; spell out anything above the BMP as well so that emoji
+        // and the like don't end up in generated source verbatim.
+        if *self as u32 > 0xFFFF {
+            write!(f, "'\\u{{{:x}}}'", *self as u32)
+        } else {
+            write!(f, "{:?}", self)
+        }
+    }
+}
+
 /// `impl PhfBorrow<T> for T`
 macro_rules! impl_reflexive(
     ($($t:ty),*) => (
@@ -185,6 +391,8 @@ impl_reflexive!(
     u128,
     i128,
     bool,
+    f32,
+    f64,
     [u8]
 );
 
@@ -231,6 +439,15 @@ impl<'a, T: 'a + FmtConst + ?Sized> FmtConst for &'a T {
     fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         (*self).fmt_const(f)
     }
+
+    fn check_fmt_const(&self) -> Result<(), FmtConstError> {
+        (**self).check_fmt_const()
+    }
+
+    #[cfg(feature = "std")]
+    fn try_fmt_const(&self) -> Result<String, FmtConstError> {
+        (**self).try_fmt_const()
+    }
 }
 
 impl PhfBorrow<str> for &str {
@@ -273,6 +490,28 @@ impl FmtConst for [u8] {
     }
 }
 
+/// Hashes the bytes of the string *without* the nul terminator, so a `CStr`
+/// hashes the same as its `to_bytes()`.
+impl PhfHash for core::ffi::CStr {
+    #[inline]
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().phf_hash(state)
+    }
+}
+
+/// Writes a `c"..."` literal, which requires Rust 1.77 or later to compile.
+impl FmtConst for core::ffi::CStr {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "c\"{}\"", self.to_bytes().escape_ascii())
+    }
+}
+
+impl PhfBorrow<core::ffi::CStr> for &core::ffi::CStr {
+    fn borrow(&self) -> &core::ffi::CStr {
+        self
+    }
+}
+
 #[cfg(feature = "unicase")]
 impl<S> PhfHash for unicase::UniCase<S>
 where
@@ -402,13 +641,75 @@ impl PhfHash for char {
     }
 }
 
+/// Create impls of `FmtConst`, `PhfHash` and `PhfBorrow` for `NonZero` integer types, hashing
+/// as the underlying integer.
+///
+/// `Option::unwrap` cannot be called in constants at the minimum supported Rust version, so the
+/// value is written as a `match` on the type's `new` constructor.
+macro_rules! nonzero_impl (
+    ($($t:ident),*) => (
+        $(
+            impl FmtConst for core::num::$t {
+                fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(
+                        f,
+                        "match ::core::num::{}::new({}) {{ \
+                         ::core::option::Option::Some(n) => n, \
+                         ::core::option::Option::None => ::core::panic!() }}",
+                        stringify!($t),
+                        self
+                    )
+                }
+            }
+
+            impl PhfHash for core::num::$t {
+                #[inline]
+                fn phf_hash<H: Hasher>(&self, state: &mut H) {
+                    self.get().phf_hash(state)
+                }
+            }
+
+            impl_reflexive!(core::num::$t);
+        )*
+    )
+);
+
+nonzero_impl!(
+    NonZeroU8,
+    NonZeroI8,
+    NonZeroU16,
+    NonZeroI16,
+    NonZeroU32,
+    NonZeroI32,
+    NonZeroU64,
+    NonZeroI64,
+    NonZeroUsize,
+    NonZeroIsize,
+    NonZeroU128,
+    NonZeroI128
+);
+
 // minimize duplicated code since formatting drags in quite a bit
 fn fmt_array<T: core::fmt::Debug>(array: &[T], f: &mut fmt::Formatter<'_>) -> fmt::Result {
     write!(f, "{:?}", array)
 }
 
+fn fmt_byte_array(array: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, byte) in array.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{:#04x}", byte)?;
+    }
+    f.write_str("]")
+}
+
 macro_rules! array_impl (
     ($t:ty) => (
+        array_impl!($t, fmt_array);
+    );
+    ($t:ty, $fmt:ident) => (
         impl<const N: usize> PhfHash for [$t; N] {
             #[inline]
             fn phf_hash<H: Hasher>(&self, state: &mut H) {
@@ -420,7 +721,7 @@ macro_rules! array_impl (
 
         impl<const N: usize> FmtConst for [$t; N] {
             fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                fmt_array(self, f)
+                $fmt(self, f)
             }
         }
 
@@ -432,7 +733,7 @@ macro_rules! array_impl (
     )
 );
 
-array_impl!(u8);
+array_impl!(u8, fmt_byte_array);
 array_impl!(i8);
 array_impl!(u16);
 array_impl!(i16);
@@ -506,6 +807,31 @@ macro_rules! tuple_impl {
                 )+
                 write!(f, ")")
             }
+
+            fn check_fmt_const(&self) -> Result<(), FmtConstError> {
+                #[allow(non_snake_case)]
+                let ($($t,)+) = self;
+                $(
+                    $t.check_fmt_const()?;
+                )+
+                Ok(())
+            }
+
+            #[cfg(feature = "std")]
+            fn try_fmt_const(&self) -> Result<String, FmtConstError> {
+                #[allow(non_snake_case)]
+                let ($($t,)+) = self;
+                let mut source = String::from("(");
+                let mut first = true;
+                $(
+                    if !core::mem::replace(&mut first, false) {
+                        source.push_str(", ");
+                    }
+                    source.push_str(&$t.try_fmt_const()?);
+                )+
+                source.push(')');
+                Ok(source)
+            }
         }
     };
 }
@@ -522,3 +848,78 @@ tuple_impl!(A, B, C, D, E, F, G, HT, I);
 tuple_impl!(A, B, C, D, E, F, G, HT, I, J);
 tuple_impl!(A, B, C, D, E, F, G, HT, I, J, K);
 tuple_impl!(A, B, C, D, E, F, G, HT, I, J, K, L);
+
+#[cfg(feature = "std")]
+impl PhfHash for std::net::Ipv4Addr {
+    #[inline]
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        self.octets().phf_hash(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl PhfHash for std::net::Ipv6Addr {
+    #[inline]
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        self.octets().phf_hash(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl PhfHash for std::net::IpAddr {
+    #[inline]
+    fn phf_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            std::net::IpAddr::V4(addr) => {
+                4u8.phf_hash(state);
+                addr.phf_hash(state)
+            }
+            std::net::IpAddr::V6(addr) => {
+                6u8.phf_hash(state);
+                addr.phf_hash(state)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FmtConst for std::net::Ipv4Addr {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.octets();
+        write!(f, "::std::net::Ipv4Addr::new({}, {}, {}, {})", a, b, c, d)
+    }
+}
+
+#[cfg(feature = "std")]
+impl FmtConst for std::net::Ipv6Addr {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("::std::net::Ipv6Addr::new(")?;
+        for (i, segment) in self.segments().iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:#x}", segment)?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[cfg(feature = "std")]
+impl FmtConst for std::net::IpAddr {
+    fn fmt_const(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            std::net::IpAddr::V4(addr) => {
+                f.write_str("::std::net::IpAddr::V4(")?;
+                addr.fmt_const(f)?;
+            }
+            std::net::IpAddr::V6(addr) => {
+                f.write_str("::std::net::IpAddr::V6(")?;
+                addr.fmt_const(f)?;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+#[cfg(feature = "std")]
+impl_reflexive!(std::net::Ipv4Addr, std::net::Ipv6Addr, std::net::IpAddr);